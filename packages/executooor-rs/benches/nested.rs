@@ -0,0 +1,218 @@
+//! Encoding a 3-level nested liquidation: a Balancer flash loan whose callback takes a
+//! Morpho Blue flash loan, whose callback liquidates a Morpho Blue position and swaps
+//! the seized collateral back in the liquidation callback.
+//!
+//! `reference` nests the same batch the way the crate used to: every callback payload,
+//! lender call and executor call is re-encoded with `alloy`'s generic encoder, copying
+//! the inner calls three times per level. The swap calls are built once upfront and
+//! repeated 1, 10 and 100 times to show how both scale with the nested payload size.
+//!
+//! Run with `cargo bench --bench nested`.
+
+use alloy_primitives::{address, Address, Bytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use executooor::encoder::ExecutorEncoder;
+use executooor::types::{AssetRequest, MarketParams};
+use executooor::{call_g0oyU7oCall, encode_context, exec_606BaXtCall};
+
+const DAI: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+const BALANCER_VAULT: Address = address!("BA12222222228d8Ba445958a75a0704d566BF2C8");
+const MORPHO_BLUE: Address = address!("BBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb");
+const SWAP_ROUTER: Address = address!("E592427A0AEce92De3Edee1F18E0157C05861564");
+const EXECUTOR: Address = address!("1111111111111111111111111111111111111111");
+const BORROWER: Address = address!("2222222222222222222222222222222222222222");
+const AMOUNT: U256 = U256::from_limbs([1_000_000, 0, 0, 0]);
+
+fn market() -> MarketParams {
+    MarketParams {
+        loan_token: DAI,
+        collateral_token: WETH,
+        oracle: Address::ZERO,
+        irm: Address::ZERO,
+        lltv: U256::from(860_000_000_000_000_000u64),
+    }
+}
+
+fn path() -> Bytes {
+    [WETH.as_slice(), &[0, 1, 244], DAI.as_slice()]
+        .concat()
+        .into()
+}
+
+/// Swaps the seized collateral back, inside the liquidation callback.
+fn swap(encoder: &mut ExecutorEncoder) {
+    encoder
+        .erc20_approve(WETH, SWAP_ROUTER, AMOUNT)
+        .uni_v3_exact_input_all(SWAP_ROUTER, path(), U256::ZERO, U256::MAX, None)
+        .erc20_skim(DAI, EXECUTOR);
+}
+
+fn nested_liquidation(encoder: &mut ExecutorEncoder, swap_calls: &[Bytes]) -> Bytes {
+    encoder.balancer_flash_loan_with(
+        BALANCER_VAULT,
+        &[AssetRequest {
+            asset: DAI,
+            amount: AMOUNT,
+        }],
+        |balancer| {
+            balancer.blue_flash_loan_with(MORPHO_BLUE, WETH, AMOUNT, |blue| {
+                blue.erc20_approve(DAI, MORPHO_BLUE, AMOUNT)
+                    .morpho_blue_liquidate(
+                        MORPHO_BLUE,
+                        &market(),
+                        BORROWER,
+                        AMOUNT,
+                        U256::ZERO,
+                        Some(swap_calls.to_vec()),
+                    );
+            });
+        },
+    );
+    encoder.encode_exec(U256::ZERO).data
+}
+
+mod reference {
+    use super::*;
+
+    sol! {
+        struct SolMarketParams {
+            address loanToken;
+            address collateralToken;
+            address oracle;
+            address irm;
+            uint256 lltv;
+        }
+
+        function liquidate(
+            SolMarketParams marketParams,
+            address borrower,
+            uint256 seizedAssets,
+            uint256 repaidShares,
+            bytes data
+        ) external;
+
+        function flashLoan(address asset, uint256 amount, bytes data) external;
+    }
+
+    pub mod balancer {
+        alloy_sol_types::sol! {
+            function flashLoan(
+                address recipient,
+                address[] tokens,
+                uint256[] amounts,
+                bytes userData
+            ) external;
+        }
+    }
+
+    fn callback_data(calls: Vec<Bytes>) -> Bytes {
+        (calls, Bytes::new()).abi_encode_params().into()
+    }
+
+    fn callback_call(target: Address, data_index: u64, call_data: Vec<u8>) -> Bytes {
+        call_g0oyU7oCall {
+            target,
+            value: U256::ZERO,
+            context: encode_context(target, data_index),
+            callData: call_data.into(),
+        }
+        .abi_encode()
+        .into()
+    }
+
+    pub fn nested_liquidation(swap_calls: &[Bytes]) -> Bytes {
+        let market = market();
+        let liquidate = callback_call(
+            MORPHO_BLUE,
+            1,
+            liquidateCall {
+                marketParams: SolMarketParams {
+                    loanToken: market.loan_token,
+                    collateralToken: market.collateral_token,
+                    oracle: market.oracle,
+                    irm: market.irm,
+                    lltv: market.lltv,
+                },
+                borrower: BORROWER,
+                seizedAssets: AMOUNT,
+                repaidShares: U256::ZERO,
+                data: callback_data(swap_calls.to_vec()),
+            }
+            .abi_encode(),
+        );
+
+        let blue_calls = vec![
+            ExecutorEncoder::build_erc20_approve(DAI, MORPHO_BLUE, AMOUNT),
+            liquidate,
+            ExecutorEncoder::build_erc20_approve(WETH, MORPHO_BLUE, AMOUNT),
+        ];
+        let blue = callback_call(
+            MORPHO_BLUE,
+            1,
+            flashLoanCall {
+                asset: WETH,
+                amount: AMOUNT,
+                data: callback_data(blue_calls),
+            }
+            .abi_encode(),
+        );
+
+        let balancer_calls = vec![
+            blue,
+            ExecutorEncoder::build_erc20_transfer(DAI, BALANCER_VAULT, AMOUNT),
+        ];
+        let balancer = callback_call(
+            BALANCER_VAULT,
+            3,
+            balancer::flashLoanCall {
+                recipient: EXECUTOR,
+                tokens: vec![DAI],
+                amounts: vec![AMOUNT],
+                userData: callback_data(balancer_calls),
+            }
+            .abi_encode(),
+        );
+
+        exec_606BaXtCall {
+            data: vec![balancer],
+        }
+        .abi_encode()
+        .into()
+    }
+}
+
+/// The calls swapping the seized collateral back, repeated `times`.
+fn swap_calls(times: usize) -> Vec<Bytes> {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    for _ in 0..times {
+        swap(&mut encoder);
+    }
+    encoder.flush()
+}
+
+fn bench_nested(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nested_liquidation");
+    for times in [1, 10, 100] {
+        let mut encoder = ExecutorEncoder::new(EXECUTOR);
+        assert_eq!(
+            nested_liquidation(&mut encoder, &swap_calls(times)),
+            reference::nested_liquidation(&swap_calls(times))
+        );
+
+        // The swap calls are built in the timed closure, as a strategy would build them.
+        group.bench_with_input(BenchmarkId::new("encoder", times), &times, |b, &times| {
+            b.iter(|| black_box(nested_liquidation(&mut encoder, &swap_calls(times))))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("alloy_reference", times),
+            &times,
+            |b, &times| b.iter(|| black_box(reference::nested_liquidation(&swap_calls(times)))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_nested);
+criterion_main!(benches);
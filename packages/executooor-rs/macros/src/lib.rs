@@ -0,0 +1,627 @@
+//! Procedural macros of the `executooor` crate, re-exported with its `macros` feature.
+
+use heck::ToSnakeCase;
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::{Attribute, Error, LitInt, LitStr, Result, Token};
+use syn_solidity::{File, Item, ItemContract, ItemFunction, Mutability, Spanned, Type};
+
+/// Generates an `ExecutorEncoder` extension trait from a Solidity interface.
+///
+/// The trait is named after the interface and gets one method per function, taking the
+/// called contract as `target` followed by the function's arguments:
+/// - state-changing functions push the call, with a `value: U256` argument after
+///   `target` if `payable`;
+/// - `view` and `pure` functions return a `Placeholder` reading their first return word,
+///   injected at `offset`.
+///
+/// Methods are named `{prefix}_{function_name}` in snake case, with the prefix set by
+/// `#[encoder(prefix = "...")]` on the interface. The encoder's own methods take
+/// precedence over the trait's: pick a prefix that doesn't collide with them.
+///
+/// Functions accept the following opt-in annotations:
+/// - `#[encoder(callback(data = <param>, function = "<callback>"))]`: `<param>` is the
+///   `bytes` argument the contract hands back to the executor's `<callback>`, named as
+///   in `executooor::callbacks` or by its full signature, e.g. `"onLoan(uint256,bytes)"`.
+///   `index = <n>` instead sets the callback data index, the head word of the data offset.
+///   `<param>` is replaced by `callback_calls: Option<Vec<Bytes>>`, and a `{method}_with`
+///   variant builds them with a closure. `<param>` must be the last dynamic argument.
+/// - `#[encoder(balance_of(<param>))]`, or `balance_of(<param>, token = <token_param>)`:
+///   adds a `{method}_all` variant filling the `uint256` argument `<param>` with the
+///   executor's whole balance of `token` at execution time. `token` is an extra
+///   argument, unless it is one of the function's arguments. The offset of `<param>` is
+///   computed at expansion: the user-defined types before it must be declared in the
+///   interface.
+///
+/// # Example
+/// ```ignore
+/// executooor::sol_encoder! {
+///     #[encoder(prefix = "vault")]
+///     interface VaultEncoder {
+///         /// Deposits `assets` for `receiver`.
+///         #[encoder(balance_of(assets))]
+///         function deposit(uint256 assets, address receiver) external returns (uint256);
+///
+///         #[encoder(callback(data = data, function = "onMorphoFlashLoan"))]
+///         function flashLoan(address token, uint256 amount, bytes data) external;
+///
+///         function maxWithdraw(address owner) external view returns (uint256);
+///     }
+/// }
+///
+/// use VaultEncoder as _;
+/// encoder
+///     .vault_deposit_all(vault, dai, receiver)
+///     .vault_flash_loan_with(vault, dai, amount, |inner| {
+///         inner.erc20_transfer(dai, vault, amount);
+///     });
+/// ```
+#[proc_macro]
+pub fn sol_encoder(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as Input);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct Input {
+    /// Raw tokens, re-emitted into `sol!` without the `#[encoder]` attributes.
+    tokens: TokenStream,
+    interface: ItemContract,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let tokens: TokenStream = input.fork().parse()?;
+        let file: File = input.parse()?;
+        let mut interfaces = file.items.into_iter().filter_map(|item| match item {
+            Item::Contract(contract) => Some(contract),
+            _ => None,
+        });
+        match (interfaces.next(), interfaces.next()) {
+            (Some(interface), None) => Ok(Self { tokens, interface }),
+            _ => Err(input.error("expected a single Solidity interface")),
+        }
+    }
+}
+
+/// Options of `#[encoder(...)]` on a function.
+#[derive(Default)]
+struct FunctionOptions {
+    /// The callback data argument, and an expression of its data index.
+    callback: Option<(Ident, TokenStream)>,
+    balance_of: Option<(Ident, Option<Ident>)>,
+}
+
+fn expand(input: Input) -> Result<TokenStream> {
+    let interface = &input.interface;
+    let trait_name = &interface.name.0;
+    let sol_mod = format_ident!("{}_sol", trait_name.to_string().to_snake_case());
+
+    let mut prefix = None;
+    for attr in encoder_attrs(&interface.attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("prefix") {
+                prefix = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `prefix = \"...\"`"))
+            }
+        })?;
+    }
+    let docs = doc_attrs(&interface.attrs);
+
+    let mut names = Vec::new();
+    let mut signatures = Vec::new();
+    let mut bodies = Vec::new();
+    for item in &interface.body {
+        let Item::Function(function) = item else {
+            continue;
+        };
+        let name = function.name().as_string();
+        if names.contains(&name) {
+            return Err(Error::new(
+                function.name().span(),
+                "overloaded functions are not supported",
+            ));
+        }
+        names.push(name);
+        for method in expand_function(function, &interface.body, &sol_mod, prefix.as_deref())? {
+            signatures.push(method.signature);
+            bodies.push(method.body);
+        }
+    }
+
+    let sol_body = sol_body(&input.tokens)?;
+    Ok(quote! {
+        #[allow(non_camel_case_types, non_snake_case, clippy::all)]
+        pub mod #sol_mod {
+            ::executooor::__private::alloy_sol_types::sol! {
+                #![sol(alloy_sol_types = ::executooor::__private::alloy_sol_types)]
+                #sol_body
+            }
+        }
+
+        #(#docs)*
+        pub trait #trait_name {
+            #(#signatures;)*
+        }
+
+        impl #trait_name for ::executooor::encoder::ExecutorEncoder {
+            #(#signatures #bodies)*
+        }
+    })
+}
+
+struct Method {
+    signature: TokenStream,
+    body: TokenStream,
+}
+
+struct Param {
+    /// Field of the generated call struct.
+    field: Ident,
+    /// Argument of the generated method.
+    arg: Ident,
+    sol_type: TokenStream,
+    ty: Type,
+}
+
+fn expand_function(
+    function: &ItemFunction,
+    items: &[Item],
+    sol_mod: &Ident,
+    prefix: Option<&str>,
+) -> Result<Vec<Method>> {
+    let options = function_options(&function.attrs)?;
+    let docs = doc_attrs(&function.attrs);
+    let sol_name = function.name();
+    let call = format_ident!("{}Call", sol_name.0);
+    let method = match prefix {
+        Some(prefix) => format_ident!("{}_{}", prefix, sol_name.as_string().to_snake_case()),
+        None => format_ident!("{}", sol_name.as_string().to_snake_case()),
+    };
+
+    let params = function
+        .parameters
+        .iter()
+        .enumerate()
+        .map(|(index, param)| {
+            let (field, arg) = match &param.name {
+                Some(name) => (
+                    name.0.clone(),
+                    format_ident!("{}", name.as_string().to_snake_case()),
+                ),
+                None => (format_ident!("_{}", index), format_ident!("arg{}", index)),
+            };
+            Ok(Param {
+                field,
+                arg,
+                sol_type: sol_type(&param.ty, sol_mod)?,
+                ty: param.ty.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let find = |ident: &Ident| {
+        params
+            .iter()
+            .position(|param| param.field == *ident)
+            .ok_or_else(|| Error::new(ident.span(), format!("no argument `{ident}`")))
+    };
+
+    let sol_types = quote!(::executooor::__private::alloy_sol_types);
+    let primitives = quote!(::executooor::__private::alloy_primitives);
+    let arg_decl = |param: &Param| {
+        let (arg, sol_type) = (&param.arg, &param.sol_type);
+        quote!(#arg: <#sol_type as #sol_types::SolType>::RustType)
+    };
+    let fields = |replace: Option<(usize, TokenStream)>| {
+        let fields = params.iter().enumerate().map(|(index, param)| {
+            let field = &param.field;
+            match &replace {
+                Some((replaced, value)) if *replaced == index => quote!(#field: #value),
+                _ => {
+                    let arg = &param.arg;
+                    quote!(#field: #arg)
+                }
+            }
+        });
+        quote!(#sol_mod::#call { #(#fields),* })
+    };
+
+    let mutability = function.attributes.mutability();
+    if matches!(
+        mutability,
+        Some(Mutability::View(_) | Mutability::Pure(_) | Mutability::Constant(_))
+    ) {
+        if options.callback.is_some() || options.balance_of.is_some() {
+            return Err(Error::new(
+                sol_name.span(),
+                "view functions only build placeholders",
+            ));
+        }
+        let args = params.iter().map(arg_decl);
+        let call = fields(None);
+        return Ok(vec![Method {
+            signature: quote! {
+                #(#docs)*
+                fn #method(
+                    &self,
+                    target: #primitives::Address,
+                    #(#args,)*
+                    offset: u64
+                ) -> ::executooor::Placeholder
+            },
+            body: quote!({ self.view_placeholder(target, #call, 0, offset) }),
+        }]);
+    }
+
+    let payable = matches!(mutability, Some(Mutability::Payable(_)));
+    let (value_decl, value) = if payable {
+        (quote!(value: #primitives::U256,), quote!(value))
+    } else {
+        (quote!(), quote!(#primitives::U256::ZERO))
+    };
+    let mut methods = Vec::new();
+
+    match (&options.callback, &options.balance_of) {
+        (Some(_), Some(_)) => {
+            return Err(Error::new(
+                sol_name.span(),
+                "`callback` and `balance_of` cannot be combined",
+            ))
+        }
+        (Some((data, data_index)), None) => {
+            let data_position = find(data)?;
+            if !matches!(params[data_position].ty, Type::Bytes(_)) {
+                return Err(Error::new(data.span(), "callback data must be `bytes`"));
+            }
+            if params[data_position + 1..]
+                .iter()
+                .any(|param| param.ty.is_abi_dynamic())
+            {
+                return Err(Error::new(
+                    data.span(),
+                    "callback data must be the last dynamic argument",
+                ));
+            }
+
+            let other_params = || {
+                params
+                    .iter()
+                    .enumerate()
+                    .filter(move |(index, _)| *index != data_position)
+                    .map(|(_, param)| param)
+            };
+            let args: Vec<_> = other_params().map(arg_decl).collect();
+            let arg_names: Vec<_> = other_params().map(|param| &param.arg).collect();
+            let call = fields(Some((data_position, quote!(#primitives::Bytes::new()))));
+            methods.push(Method {
+                signature: quote! {
+                    #(#docs)*
+                    fn #method(
+                        &mut self,
+                        target: #primitives::Address,
+                        #value_decl
+                        #(#args,)*
+                        callback_calls: Option<Vec<#primitives::Bytes>>
+                    ) -> &mut Self
+                },
+                body: quote!({
+                    let context = ::executooor::CallbackContext {
+                        sender: target,
+                        data_index: #data_index,
+                    };
+                    self.push_callback_call(
+                        target,
+                        #value,
+                        #call,
+                        &context,
+                        callback_calls.unwrap_or_default(),
+                        #primitives::Bytes::new(),
+                    )
+                }),
+            });
+
+            let with = format_ident!("{}_with", method);
+            let doc = format!(
+                " Like [`{method}`](Self::{method}), with the callback calls pushed by \
+                 `callback` onto a sub-encoder."
+            );
+            let value_arg = payable.then(|| quote!(value,));
+            methods.push(Method {
+                signature: quote! {
+                    #[doc = #doc]
+                    fn #with(
+                        &mut self,
+                        target: #primitives::Address,
+                        #value_decl
+                        #(#args,)*
+                        callback: impl FnOnce(&mut ::executooor::encoder::ExecutorEncoder)
+                    ) -> &mut Self
+                },
+                body: quote!({
+                    let callback_calls = self.build_callback(callback);
+                    self.#method(target, #value_arg #(#arg_names,)* Some(callback_calls))
+                }),
+            });
+        }
+        (None, balance_of) => {
+            let args = params.iter().map(arg_decl);
+            let call = fields(None);
+            methods.push(Method {
+                signature: quote! {
+                    #(#docs)*
+                    fn #method(
+                        &mut self,
+                        target: #primitives::Address,
+                        #value_decl
+                        #(#args),*
+                    ) -> &mut Self
+                },
+                body: quote!({ self.push_sol_call_with(target, #value, None, vec![], #call) }),
+            });
+
+            if let Some((amount, token)) = balance_of {
+                let amount_position = find(amount)?;
+                let is_uint256 = match params[amount_position].ty {
+                    Type::Uint(_, size) => size.is_none_or(|size| size.get() == 256),
+                    _ => false,
+                };
+                if !is_uint256 {
+                    return Err(Error::new(amount.span(), "balance must be a `uint256`"));
+                }
+                let (token_decl, token_arg) = match token {
+                    Some(token) => (quote!(), params[find(token)?].arg.clone()),
+                    None => (quote!(token: #primitives::Address,), format_ident!("token")),
+                };
+                let args = params
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| *index != amount_position)
+                    .map(|(_, param)| arg_decl(param));
+                let call = fields(Some((amount_position, quote!(#primitives::U256::ZERO))));
+                let mut head_words = 0;
+                for param in &params[..amount_position] {
+                    head_words += static_words(&param.ty, items)?.unwrap_or(1);
+                }
+                let offset = (4 + 32 * head_words) as u64;
+                let all = format_ident!("{}_all", method);
+                let doc = format!(
+                    " Like [`{method}`](Self::{method}), with `{}` set to the executor's \
+                     whole balance of `{token_arg}` at execution time.",
+                    params[amount_position].arg
+                );
+                methods.push(Method {
+                    signature: quote! {
+                        #[doc = #doc]
+                        fn #all(
+                            &mut self,
+                            target: #primitives::Address,
+                            #value_decl
+                            #token_decl
+                            #(#args),*
+                        ) -> &mut Self
+                    },
+                    body: quote!({
+                        let placeholder =
+                            self.erc20_balance_of(#token_arg, self.address(), #offset);
+                        self.push_sol_call_with(target, #value, None, vec![placeholder], #call)
+                    }),
+                });
+            }
+        }
+    }
+    Ok(methods)
+}
+
+fn function_options(attrs: &[Attribute]) -> Result<FunctionOptions> {
+    let mut options = FunctionOptions::default();
+    for attr in encoder_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("callback") {
+                let (mut data, mut index) = (None, None);
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("data") {
+                        data = Some(meta.value()?.parse::<Ident>()?);
+                    } else if index.is_some() {
+                        return Err(meta.error("expected a single `index` or `function`"));
+                    } else if meta.path.is_ident("index") {
+                        let index_lit = meta.value()?.parse::<LitInt>()?;
+                        let value: u64 = index_lit.base10_parse()?;
+                        index = Some(quote!(#value));
+                    } else if meta.path.is_ident("function") {
+                        let function = meta.value()?.parse::<LitStr>()?;
+                        index = Some(quote_spanned! {function.span()=>
+                            const {
+                                match ::executooor::callbacks::resolve_data_index(#function) {
+                                    Some(index) => index,
+                                    None => panic!(concat!(
+                                        "unknown callback `",
+                                        #function,
+                                        "`: use its full signature, with a single `bytes` argument"
+                                    )),
+                                }
+                            }
+                        });
+                    } else {
+                        return Err(meta.error("expected `data`, `index` or `function`"));
+                    }
+                    Ok(())
+                })?;
+                match (data, index) {
+                    (Some(data), Some(index)) => options.callback = Some((data, index)),
+                    _ => {
+                        return Err(meta.error(
+                            "expected `callback(data = .., function = ..)` or `callback(data = .., index = ..)`",
+                        ))
+                    }
+                }
+            } else if meta.path.is_ident("balance_of") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let amount: Ident = content.parse()?;
+                let mut token = None;
+                if content.parse::<Option<Token![,]>>()?.is_some() {
+                    let key: Ident = content.parse()?;
+                    if key != "token" {
+                        return Err(Error::new(key.span(), "expected `token = ..`"));
+                    }
+                    content.parse::<Token![=]>()?;
+                    token = Some(content.parse()?);
+                }
+                options.balance_of = Some((amount, token));
+            } else {
+                return Err(meta.error("expected `callback(..)` or `balance_of(..)`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+fn encoder_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("encoder"))
+}
+
+fn doc_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("doc"))
+}
+
+/// Returns the `sol_data` type encoding `ty`.
+fn sol_type(ty: &Type, sol_mod: &Ident) -> Result<TokenStream> {
+    let sol_data = quote!(::executooor::__private::alloy_sol_types::sol_data);
+    let size = |size: Option<std::num::NonZeroU16>| {
+        proc_macro2::Literal::usize_unsuffixed(size.map_or(256, |size| size.get().into()))
+    };
+    Ok(match ty {
+        Type::Address(..) => quote!(#sol_data::Address),
+        Type::Bool(_) => quote!(#sol_data::Bool),
+        Type::String(_) => quote!(#sol_data::String),
+        Type::Bytes(_) => quote!(#sol_data::Bytes),
+        Type::FixedBytes(_, size) => {
+            let size = proc_macro2::Literal::usize_unsuffixed(size.get().into());
+            quote!(#sol_data::FixedBytes<#size>)
+        }
+        Type::Int(_, bits) => {
+            let bits = size(*bits);
+            quote!(#sol_data::Int<#bits>)
+        }
+        Type::Uint(_, bits) => {
+            let bits = size(*bits);
+            quote!(#sol_data::Uint<#bits>)
+        }
+        Type::Array(array) => {
+            let element = sol_type(&array.ty, sol_mod)?;
+            match array.size() {
+                Some(len) => {
+                    let len = proc_macro2::Literal::usize_unsuffixed(len);
+                    quote!(#sol_data::FixedArray<#element, #len>)
+                }
+                None => quote!(#sol_data::Array<#element>),
+            }
+        }
+        Type::Tuple(tuple) => {
+            let types = tuple
+                .types
+                .iter()
+                .map(|ty| sol_type(ty, sol_mod))
+                .collect::<Result<Vec<_>>>()?;
+            quote!((#(#types,)*))
+        }
+        Type::Custom(path) => {
+            let segments = path.iter().map(|ident| &ident.0);
+            quote!(#sol_mod::#(#segments)::*)
+        }
+        Type::Function(_) | Type::Mapping(_) => {
+            return Err(Error::new(ty.span(), "unsupported argument type"))
+        }
+    })
+}
+
+/// Returns the number of words taken by a value of type `ty`, or `None` if it is dynamic.
+///
+/// User-defined types resolve to the structs, enums and value types of `items`.
+fn static_words(ty: &Type, items: &[Item]) -> Result<Option<usize>> {
+    Ok(match ty {
+        Type::Address(..)
+        | Type::Bool(_)
+        | Type::FixedBytes(..)
+        | Type::Int(..)
+        | Type::Uint(..) => Some(1),
+        Type::String(_) | Type::Bytes(_) => None,
+        Type::Array(array) => match array.size() {
+            Some(len) => static_words(&array.ty, items)?.map(|words| words * len),
+            None => None,
+        },
+        Type::Tuple(tuple) => sum_words(tuple.types.iter(), items)?,
+        Type::Custom(path) => {
+            let name = path.last();
+            match items.iter().find(|item| item.name() == Some(name)) {
+                Some(Item::Struct(item)) => {
+                    sum_words(item.fields.iter().map(|field| &field.ty), items)?
+                }
+                Some(Item::Enum(_)) => Some(1),
+                Some(Item::Udt(item)) => static_words(&item.ty, items)?,
+                _ => return Err(Error::new(name.span(), format!("unknown type `{name}`"))),
+            }
+        }
+        Type::Function(_) | Type::Mapping(_) => {
+            return Err(Error::new(ty.span(), "unsupported argument type"))
+        }
+    })
+}
+
+/// Returns the number of words taken by a tuple of `types`, or `None` if it is dynamic.
+fn sum_words<'a>(types: impl Iterator<Item = &'a Type>, items: &[Item]) -> Result<Option<usize>> {
+    let mut words = 0;
+    for ty in types {
+        match static_words(ty, items)? {
+            Some(ty_words) => words += ty_words,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(words))
+}
+
+/// Extracts the interface body from the input, strips the `#[encoder]` attributes and
+/// derives the ABI of every function, for `executooor::sol_arg_offset` on the generated
+/// calls.
+fn sol_body(tokens: &TokenStream) -> Result<TokenStream> {
+    let body = tokens
+        .clone()
+        .into_iter()
+        .find_map(|token| match token {
+            TokenTree::Group(group) if group.delimiter() == proc_macro2::Delimiter::Brace => {
+                Some(group.stream())
+            }
+            _ => None,
+        })
+        .ok_or_else(|| Error::new(Span::call_site(), "expected an interface body"))?;
+
+    let mut output = TokenStream::new();
+    let mut tokens = body.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                if let Some(TokenTree::Group(group)) = tokens.peek() {
+                    let is_encoder = matches!(
+                        group.stream().into_iter().next(),
+                        Some(TokenTree::Ident(ident)) if ident == "encoder"
+                    );
+                    if is_encoder {
+                        tokens.next();
+                        continue;
+                    }
+                }
+            }
+            TokenTree::Ident(ident) if ident == "function" => {
+                output.extend(quote!(#[sol(abi)]));
+            }
+            _ => {}
+        }
+        output.extend([token]);
+    }
+    Ok(output)
+}
@@ -0,0 +1,188 @@
+//! Direct ABI encoding of the executor's own calls.
+//!
+//! Nested batches embed already encoded calls as `bytes` at every callback level.
+//! `alloy`'s generic encoder goes through an intermediate word buffer, copying the
+//! nested payloads more than once per level. The encoders below compute the exact
+//! output size of each level upfront, so encoding costs one allocation and one copy
+//! per nesting level: every callback level gets its own buffer, into which the
+//! already encoded payload of the level below is copied.
+
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_sol_types::SolCall;
+
+use crate::{call_g0oyU7oCall, Placeholder};
+
+/// Size of `len` bytes padded to a multiple of 32.
+const fn padded(len: usize) -> usize {
+    len.div_ceil(32) * 32
+}
+
+/// Size of the tail encoding of a `bytes` value: its length word and padded data.
+const fn bytes_size(len: usize) -> usize {
+    32 + padded(len)
+}
+
+/// Size of the tail encoding of a `bytes[]` value.
+fn bytes_array_size(items: &[Bytes]) -> usize {
+    32 + items
+        .iter()
+        .map(|item| 32 + bytes_size(item.len()))
+        .sum::<usize>()
+}
+
+/// Size of the tail encoding of a `Placeholder[]` value.
+fn placeholders_size(placeholders: &[Placeholder]) -> usize {
+    32 + placeholders
+        .iter()
+        .map(|placeholder| 32 + 5 * 32 + bytes_size(placeholder.data.len()))
+        .sum::<usize>()
+}
+
+fn push_usize(out: &mut Vec<u8>, value: usize) {
+    out.extend_from_slice(&U256::from(value).to_be_bytes::<32>());
+}
+
+fn push_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&[0; 24]);
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn push_address(out: &mut Vec<u8>, address: Address) {
+    out.extend_from_slice(&[0; 12]);
+    out.extend_from_slice(address.as_slice());
+}
+
+fn push_bytes(out: &mut Vec<u8>, data: &[u8]) {
+    push_usize(out, data.len());
+    out.extend_from_slice(data);
+    out.resize(out.len() + padded(data.len()) - data.len(), 0);
+}
+
+fn push_bytes_array(out: &mut Vec<u8>, items: &[Bytes]) {
+    push_usize(out, items.len());
+    let mut offset = 32 * items.len();
+    for item in items {
+        push_usize(out, offset);
+        offset += bytes_size(item.len());
+    }
+    for item in items {
+        push_bytes(out, item);
+    }
+}
+
+fn push_placeholders(out: &mut Vec<u8>, placeholders: &[Placeholder]) {
+    push_usize(out, placeholders.len());
+    let mut offset = 32 * placeholders.len();
+    for placeholder in placeholders {
+        push_usize(out, offset);
+        offset += 5 * 32 + bytes_size(placeholder.data.len());
+    }
+    for placeholder in placeholders {
+        push_address(out, placeholder.to);
+        // `data` is encoded right after the 5-word tuple head.
+        push_usize(out, 5 * 32);
+        push_u64(out, placeholder.offset);
+        push_u64(out, placeholder.length);
+        push_u64(out, placeholder.resOffset);
+        push_bytes(out, &placeholder.data);
+    }
+}
+
+/// Encodes `call_g0oyU7o(target, value, context, callData)`, or
+/// `callWithPlaceholders4845164670(..., placeholders)` if `placeholders` is non-empty.
+pub(crate) fn encode_call(
+    selector: [u8; 4],
+    target: Address,
+    value: U256,
+    context: B256,
+    call_data: &[u8],
+    placeholders: &[Placeholder],
+) -> Bytes {
+    let head_words = if placeholders.is_empty() { 4 } else { 5 };
+    let mut size = 4 + 32 * head_words + bytes_size(call_data.len());
+    if !placeholders.is_empty() {
+        size += placeholders_size(placeholders);
+    }
+
+    let mut out = Vec::with_capacity(size);
+    out.extend_from_slice(&selector);
+    push_address(&mut out, target);
+    out.extend_from_slice(&value.to_be_bytes::<32>());
+    out.extend_from_slice(context.as_slice());
+    push_usize(&mut out, 32 * head_words);
+    if !placeholders.is_empty() {
+        push_usize(&mut out, 32 * head_words + bytes_size(call_data.len()));
+    }
+    push_bytes(&mut out, call_data);
+    if !placeholders.is_empty() {
+        push_placeholders(&mut out, placeholders);
+    }
+    debug_assert_eq!(out.len(), size);
+    out.into()
+}
+
+/// Size of `abi.encode(calls, returnValue)`.
+fn callback_data_size(calls: &[Bytes], return_value: &[u8]) -> usize {
+    64 + bytes_array_size(calls) + bytes_size(return_value.len())
+}
+
+fn push_callback_data(out: &mut Vec<u8>, calls: &[Bytes], return_value: &[u8]) {
+    let array_size = bytes_array_size(calls);
+    push_usize(out, 64);
+    push_usize(out, 64 + array_size);
+    push_bytes_array(out, calls);
+    push_bytes(out, return_value);
+}
+
+/// Encodes `abi.encode(calls, returnValue)`.
+pub(crate) fn encode_callback_data(calls: &[Bytes], return_value: &[u8]) -> Bytes {
+    let size = callback_data_size(calls, return_value);
+
+    let mut out = Vec::with_capacity(size);
+    push_callback_data(&mut out, calls, return_value);
+    debug_assert_eq!(out.len(), size);
+    out.into()
+}
+
+/// Encodes `call_g0oyU7o(target, value, context, callData)` where `callData` is `head`
+/// followed by the `bytes` encoding of `abi.encode(calls, returnValue)`.
+///
+/// `head` is the encoding of the inner call up to, excluding, the length word of its
+/// last dynamic argument, the callback data.
+pub(crate) fn encode_callback_call(
+    target: Address,
+    value: U256,
+    context: B256,
+    head: &[u8],
+    calls: &[Bytes],
+    return_value: &[u8],
+) -> Bytes {
+    let payload_size = callback_data_size(calls, return_value);
+    let call_data_size = head.len() + 32 + payload_size;
+    let size = 4 + 4 * 32 + bytes_size(call_data_size);
+
+    let mut out = Vec::with_capacity(size);
+    out.extend_from_slice(&call_g0oyU7oCall::SELECTOR);
+    push_address(&mut out, target);
+    out.extend_from_slice(&value.to_be_bytes::<32>());
+    out.extend_from_slice(context.as_slice());
+    push_usize(&mut out, 4 * 32);
+    push_usize(&mut out, call_data_size);
+    out.extend_from_slice(head);
+    push_usize(&mut out, payload_size);
+    push_callback_data(&mut out, calls, return_value);
+    out.resize(size, 0);
+    out.into()
+}
+
+/// Encodes `selector(bytes[] calls)`, e.g. `exec_606BaXt`.
+pub(crate) fn encode_bytes_array_call(selector: [u8; 4], calls: &[Bytes]) -> Bytes {
+    let size = 4 + 32 + bytes_array_size(calls);
+
+    let mut out = Vec::with_capacity(size);
+    out.extend_from_slice(&selector);
+    push_usize(&mut out, 32);
+    push_bytes_array(&mut out, calls);
+    debug_assert_eq!(out.len(), size);
+    out.into()
+}
@@ -0,0 +1,106 @@
+//! Protocol actions as values, to build, inspect and compose plans generically.
+//!
+//! Every protocol module exposes its operations both as [`ExecutorEncoder`] methods and
+//! as structs implementing [`Action`], which delegate to those methods. Downstream
+//! crates add protocols by implementing [`Action`] for their own types.
+//!
+//! # Example
+//! ```ignore
+//! let plan: Vec<Box<dyn Action>> = vec![Box::new(BlueFlashLoan {
+//!     morpho_blue,
+//!     asset: dai,
+//!     amount,
+//!     callback: vec![
+//!         Box::new(Erc4626DepositAll { vault, asset: dai, owner: executor }),
+//!         Box::new(Erc4626RedeemAll { vault, asset: dai, receiver: executor, owner: executor }),
+//!     ],
+//! })];
+//! encode_actions(&mut encoder, &plan)?;
+//! ```
+
+use std::fmt;
+
+use alloy_primitives::{Address, Bytes};
+
+use crate::encoder::ExecutorEncoder;
+use crate::error::Error;
+
+/// An operation that encodes itself as executor calls.
+pub trait Action: fmt::Debug {
+    /// Pushes the calls of this action onto `encoder`, along with its callback actions.
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error>;
+
+    /// Returns the tokens this action takes from the executor.
+    fn consumes(&self) -> Vec<Address> {
+        Vec::new()
+    }
+
+    /// Returns the tokens this action outputs, to the executor or an explicit receiver.
+    fn produces(&self) -> Vec<Address> {
+        Vec::new()
+    }
+
+    /// Returns the actions executed in this action's callback.
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &[]
+    }
+}
+
+impl<A: Action + ?Sized> Action for Box<A> {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        (**self).encode(encoder)
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        (**self).consumes()
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        (**self).produces()
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        (**self).callback()
+    }
+}
+
+/// Pushes every action of `actions` onto `encoder`, in order.
+///
+/// Stops at the first failing action, which pushes nothing, but leaves the previous
+/// ones pushed: take a [`checkpoint`](ExecutorEncoder::checkpoint) to roll them back.
+pub fn encode_actions<A: Action>(
+    encoder: &mut ExecutorEncoder,
+    actions: &[A],
+) -> Result<(), Error> {
+    actions.iter().try_for_each(|action| action.encode(encoder))
+}
+
+/// Encodes callback actions on a sub-encoder, carrying their value up to `encoder`.
+///
+/// The result is the `callback_calls` argument of the encoder's callback methods.
+pub fn encode_callback<A: Action>(
+    encoder: &mut ExecutorEncoder,
+    actions: &[A],
+) -> Result<Option<Vec<Bytes>>, Error> {
+    let mut inner = ExecutorEncoder::new(encoder.address());
+    encode_actions(&mut inner, actions)?;
+    Ok(Some(encoder.try_nest_callback(&mut inner)?))
+}
+
+/// Encodes `actions` as the callback of the call `push` builds from their calls.
+///
+/// Drops the callback if `push` fails, so that the failing action pushes nothing.
+pub(crate) fn encode_with_callback<F>(
+    encoder: &mut ExecutorEncoder,
+    actions: &[Box<dyn Action>],
+    push: F,
+) -> Result<(), Error>
+where
+    F: FnOnce(&mut ExecutorEncoder, Option<Vec<Bytes>>) -> Result<&mut ExecutorEncoder, Error>,
+{
+    encoder.try_push_with_callback(
+        |inner| encode_actions(inner, actions),
+        |encoder, callback_calls| push(encoder, Some(callback_calls)),
+    )?;
+    Ok(())
+}
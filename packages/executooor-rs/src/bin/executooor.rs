@@ -0,0 +1,184 @@
+//! Offline command-line interface to the executooor encoder.
+//!
+//! ```text
+//! executooor encode --strategy rescue.toml
+//! executooor encode --executor 0x.. --action "wrap_eth weth=0x.. amount=1000"
+//! executooor explain 0x00000001... --label 0x6B17..=DAI
+//! executooor context encode 0xBA12222222228d8Ba445958a75a0704d566BF2C8 3
+//! executooor deploy-bytecode 0x..
+//! ```
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use alloy_primitives::{hex, Address, B256, U256};
+use clap::{Parser, Subcommand};
+use executooor::strategy::{Strategy, StrategyAction};
+use executooor::{decode_context, decode_exec, deploy_bytecode, encode_context, Explainer};
+
+#[derive(Parser)]
+#[command(
+    name = "executooor",
+    version,
+    about = "Encode, decode and explain Executor calldata"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Encodes an `exec_606BaXt` transaction from a strategy file or a list of actions.
+    Encode {
+        /// TOML or JSON strategy file (by extension, TOML otherwise).
+        #[arg(long, conflicts_with_all = ["executor", "action"])]
+        strategy: Option<PathBuf>,
+        /// Executor contract address, when building from `--action`s.
+        #[arg(long, required_unless_present = "strategy")]
+        executor: Option<Address>,
+        /// ETH sent on top of the value spent by the actions.
+        #[arg(long, default_value_t = U256::ZERO)]
+        extra_value: U256,
+        /// An action as `name key=value ...`, e.g. `"wrap_eth weth=0x.. amount=1000"`.
+        /// Values starting with `[` or `{` are parsed as JSON without spaces (e.g. `callback=[...]`).
+        #[arg(long)]
+        action: Vec<String>,
+    },
+    /// Decodes `exec_606BaXt` calldata into its call tree, as JSON.
+    Decode {
+        /// Hex calldata, read from stdin if omitted.
+        data: Option<String>,
+    },
+    /// Explains `exec_606BaXt` calldata, one line per call.
+    Explain {
+        /// Hex calldata, read from stdin if omitted.
+        data: Option<String>,
+        /// Displays an address by name, as `address=name`.
+        #[arg(long, value_parser = parse_label)]
+        label: Vec<(Address, String)>,
+    },
+    /// Encodes or decodes a bytes32 callback context.
+    #[command(subcommand)]
+    Context(ContextCommand),
+    /// Prints the creation code deploying an Executor owned by `owner`.
+    DeployBytecode { owner: Address },
+}
+
+#[derive(Subcommand)]
+enum ContextCommand {
+    /// Encodes the context expecting a callback from `sender` with its data at `data_index`.
+    Encode { sender: Address, data_index: u64 },
+    /// Decodes a context into its sender and data index.
+    Decode { context: B256 },
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<String, Box<dyn std::error::Error>> {
+    match command {
+        Command::Encode {
+            strategy,
+            executor,
+            extra_value,
+            action,
+        } => {
+            let strategy = match strategy {
+                Some(path) => {
+                    let source = std::fs::read_to_string(&path)?;
+                    let mut strategy = match path.extension().and_then(|ext| ext.to_str()) {
+                        Some("json") => Strategy::from_json(&source)?,
+                        _ => Strategy::from_toml(&source)?,
+                    };
+                    strategy.extra_value += extra_value;
+                    strategy
+                }
+                None => Strategy {
+                    executor: executor.ok_or("missing --executor")?,
+                    extra_value,
+                    actions: action
+                        .iter()
+                        .map(|action| parse_action(action))
+                        .collect::<Result<_, _>>()?,
+                },
+            };
+            Ok(serde_json::to_string_pretty(&strategy.compile()?)?)
+        }
+        Command::Decode { data } => {
+            let calls = decode_exec(&read_hex(data)?)?;
+            Ok(serde_json::to_string_pretty(&calls)?)
+        }
+        Command::Explain { data, label } => {
+            let explainer = label
+                .into_iter()
+                .fold(Explainer::new(), |explainer, (address, name)| {
+                    explainer.label(address, name)
+                });
+            let explanation = explainer.explain_exec(&read_hex(data)?)?;
+            Ok(explanation.trim_end().to_string())
+        }
+        Command::Context(ContextCommand::Encode { sender, data_index }) => {
+            Ok(encode_context(sender, data_index).to_string())
+        }
+        Command::Context(ContextCommand::Decode { context }) => {
+            let (sender, data_index) =
+                decode_context(context).ok_or("data index does not fit in 8 bytes")?;
+            Ok(format!("sender: {sender}\ndata index: {data_index}"))
+        }
+        Command::DeployBytecode { owner } => Ok(deploy_bytecode(owner).to_string()),
+    }
+}
+
+/// Parses `name key=value ...` into the strategy action of the same name.
+fn parse_action(action: &str) -> Result<StrategyAction, Box<dyn std::error::Error>> {
+    let mut words = action.split_whitespace();
+    let name = words.next().ok_or("empty action")?;
+
+    let mut fields = serde_json::Map::new();
+    fields.insert("action".to_string(), name.into());
+    for word in words {
+        let (key, value) = word
+            .split_once('=')
+            .ok_or_else(|| format!("expected `key=value`, got `{word}`"))?;
+        let value = if value.starts_with('[') || value.starts_with('{') {
+            serde_json::from_str(value)?
+        } else {
+            value.into()
+        };
+        fields.insert(key.to_string(), value);
+    }
+    Ok(serde_json::from_value(fields.into())?)
+}
+
+fn parse_label(label: &str) -> Result<(Address, String), String> {
+    let (address, name) = label
+        .split_once('=')
+        .ok_or_else(|| format!("expected `address=name`, got `{label}`"))?;
+    let address = address.parse().map_err(|err| format!("{err}"))?;
+    Ok((address, name.to_string()))
+}
+
+/// Reads hex data from the argument, or from stdin if omitted.
+fn read_hex(data: Option<String>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let data = match data {
+        Some(data) => data,
+        None => {
+            let mut data = String::new();
+            std::io::stdin().read_to_string(&mut data)?;
+            data
+        }
+    };
+    Ok(hex::decode(data.trim())?)
+}
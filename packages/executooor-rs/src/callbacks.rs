@@ -0,0 +1,447 @@
+//! Callback functions that lenders and protocols call on the executor.
+//!
+//! The executor finds the calls to run in a callback in its `bytes` argument, at the
+//! `data_index` of the [`CallbackContext`] pushed along with the call triggering it.
+//! The index is derived from the callback signature here, rather than hardcoded.
+//!
+//! # Example
+//! ```ignore
+//! // A lender calling back `onFlashLoan(address,address,uint256,uint256,bytes)`.
+//! let ctx = CallbackContext::for_callback(lender, "onFlashLoan")?;
+//! // A lender unknown to the registry, by its full signature.
+//! let ctx = CallbackContext::for_callback(lender, "onLoan(address,uint256,bytes)")?;
+//! ```
+
+use alloy_primitives::Address;
+use alloy_sol_types::SolCall;
+
+use crate::error::Error;
+use crate::types::CallbackContext;
+
+pub(crate) mod callbacks_sol {
+    use alloy_sol_types::sol;
+
+    sol! {
+        function receiveFlashLoan(
+            address[] tokens,
+            uint256[] amounts,
+            uint256[] feeAmounts,
+            bytes userData
+        ) external;
+
+        function onFlashLoan(
+            address initiator,
+            address token,
+            uint256 amount,
+            uint256 fee,
+            bytes data
+        ) external returns (bytes32);
+
+        function executeOperation(
+            address[] assets,
+            uint256[] amounts,
+            uint256[] premiums,
+            address initiator,
+            bytes params
+        ) external returns (bool);
+
+        function uniswapV2Call(
+            address sender,
+            uint256 amount0,
+            uint256 amount1,
+            bytes data
+        ) external;
+
+        function uniswapV3FlashCallback(uint256 fee0, uint256 fee1, bytes data) external;
+
+        function uniswapV3SwapCallback(
+            int256 amount0Delta,
+            int256 amount1Delta,
+            bytes data
+        ) external;
+
+        function onMorphoFlashLoan(uint256 assets, bytes data) external;
+        function onMorphoSupply(uint256 assets, bytes data) external;
+        function onMorphoSupplyCollateral(uint256 assets, bytes data) external;
+        function onMorphoRepay(uint256 assets, bytes data) external;
+        function onMorphoLiquidate(uint256 repaidAssets, bytes data) external;
+    }
+}
+
+/// A callback function, with the head word of its callback data argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallbackSignature {
+    pub signature: &'static str,
+    pub selector: [u8; 4],
+    pub data_index: u64,
+}
+
+impl CallbackSignature {
+    /// Returns the function name, e.g. `onFlashLoan`.
+    pub fn name(&self) -> &'static str {
+        self.signature
+            .split_once('(')
+            .map_or(self.signature, |(name, _)| name)
+    }
+
+    /// Returns the context of a call that `sender` calls back with this function.
+    pub fn context(&self, sender: Address) -> CallbackContext {
+        CallbackContext {
+            sender,
+            data_index: self.data_index,
+        }
+    }
+}
+
+const fn known<C: SolCall>() -> CallbackSignature {
+    match data_index(C::SIGNATURE) {
+        Some(data_index) => CallbackSignature {
+            signature: C::SIGNATURE,
+            selector: C::SELECTOR,
+            data_index,
+        },
+        None => panic!("callbacks must have exactly one `bytes` argument"),
+    }
+}
+
+/// Balancer flash loans.
+pub const RECEIVE_FLASH_LOAN: CallbackSignature = known::<callbacks_sol::receiveFlashLoanCall>();
+/// ERC3156 flash loans, e.g. Maker's.
+pub const ON_FLASH_LOAN: CallbackSignature = known::<callbacks_sol::onFlashLoanCall>();
+/// Aave flash loans.
+pub const EXECUTE_OPERATION: CallbackSignature = known::<callbacks_sol::executeOperationCall>();
+/// Uniswap V2 flash swaps.
+pub const UNISWAP_V2_CALL: CallbackSignature = known::<callbacks_sol::uniswapV2CallCall>();
+/// Uniswap V3 flash loans.
+pub const UNISWAP_V3_FLASH_CALLBACK: CallbackSignature =
+    known::<callbacks_sol::uniswapV3FlashCallbackCall>();
+/// Uniswap V3 swaps, paid in the callback.
+pub const UNISWAP_V3_SWAP_CALLBACK: CallbackSignature =
+    known::<callbacks_sol::uniswapV3SwapCallbackCall>();
+/// Morpho Blue flash loans.
+pub const ON_MORPHO_FLASH_LOAN: CallbackSignature = known::<callbacks_sol::onMorphoFlashLoanCall>();
+/// Morpho Blue supplies.
+pub const ON_MORPHO_SUPPLY: CallbackSignature = known::<callbacks_sol::onMorphoSupplyCall>();
+/// Morpho Blue collateral supplies.
+pub const ON_MORPHO_SUPPLY_COLLATERAL: CallbackSignature =
+    known::<callbacks_sol::onMorphoSupplyCollateralCall>();
+/// Morpho Blue repayments.
+pub const ON_MORPHO_REPAY: CallbackSignature = known::<callbacks_sol::onMorphoRepayCall>();
+/// Morpho Blue liquidations.
+pub const ON_MORPHO_LIQUIDATE: CallbackSignature = known::<callbacks_sol::onMorphoLiquidateCall>();
+
+/// Every callback known to the crate.
+pub const KNOWN_CALLBACKS: &[CallbackSignature] = &[
+    RECEIVE_FLASH_LOAN,
+    ON_FLASH_LOAN,
+    EXECUTE_OPERATION,
+    UNISWAP_V2_CALL,
+    UNISWAP_V3_FLASH_CALLBACK,
+    UNISWAP_V3_SWAP_CALLBACK,
+    ON_MORPHO_FLASH_LOAN,
+    ON_MORPHO_SUPPLY,
+    ON_MORPHO_SUPPLY_COLLATERAL,
+    ON_MORPHO_REPAY,
+    ON_MORPHO_LIQUIDATE,
+];
+
+/// Returns the known callback called `name`, or with the full signature `name`.
+pub const fn find_callback(name: &str) -> Option<&'static CallbackSignature> {
+    let mut i = 0;
+    while i < KNOWN_CALLBACKS.len() {
+        let callback = &KNOWN_CALLBACKS[i];
+        let signature = callback.signature.as_bytes();
+        let name_len = signature.len() - params_len(signature);
+        if bytes_eq(name.as_bytes(), signature, 0, signature.len())
+            || bytes_eq(name.as_bytes(), signature, 0, name_len)
+        {
+            return Some(callback);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns the data index of the known callback `name`, or else of the callback with
+/// the full signature `name`, e.g. 1 for `onLoan(uint256,bytes)` (see [`data_index`]).
+///
+/// `const` version of [`callback_data_index`], for generated code.
+pub const fn resolve_data_index(name: &str) -> Option<u64> {
+    match find_callback(name) {
+        Some(callback) => Some(callback.data_index),
+        None => data_index(name),
+    }
+}
+
+/// Returns the data index of the known callback `name`, or else of the callback with
+/// the full signature `name`.
+///
+/// Returns [`Error::UnknownCallback`] if `name` is neither a known callback nor a
+/// signature, and [`Error::InvalidCallbackSignature`] if the signature doesn't have
+/// exactly one top-level `bytes` argument.
+pub fn callback_data_index(name: &str) -> Result<u64, Error> {
+    resolve_data_index(name).ok_or_else(|| {
+        if name.contains('(') {
+            Error::InvalidCallbackSignature(name.to_string())
+        } else {
+            Error::UnknownCallback(name.to_string())
+        }
+    })
+}
+
+/// Returns the head word of the only top-level `bytes` argument of `signature`, i.e. `i`
+/// such that the executor reads the offset of the callback data at `4 + 32 * i`.
+///
+/// Dynamic arguments take one head word; static tuples and fixed-size arrays take as many
+/// as they hold static words, e.g. 3 for `onLoan((uint256,address),uint256,bytes)`.
+/// Arguments may be named, as in `onLoan(uint256 amount, bytes calldata data)`.
+/// `None` if the signature is malformed, has no or several `bytes` arguments, or has an
+/// argument of unknown size, such as a struct or enum referred to by name.
+pub const fn data_index(signature: &str) -> Option<u64> {
+    match signature_layout(signature.as_bytes()) {
+        Some(layout) if layout.bytes_count == 1 => layout.bytes_head,
+        _ => None,
+    }
+}
+
+/// Returns the head word of the last dynamic top-level argument of `signature`, if it is
+/// a `bytes`, i.e. whose encoding ends the call data.
+pub(crate) const fn trailing_bytes_head(signature: &str) -> Option<u64> {
+    match signature_layout(signature.as_bytes()) {
+        Some(ArgsLayout {
+            last_dynamic_head: Some(head),
+            last_dynamic_is_bytes: true,
+            ..
+        }) => Some(head),
+        _ => None,
+    }
+}
+
+/// Head layout of a comma-separated argument list.
+#[derive(Clone, Copy)]
+struct ArgsLayout {
+    /// Total head words.
+    words: u64,
+    dynamic: bool,
+    bytes_count: u64,
+    bytes_head: Option<u64>,
+    last_dynamic_head: Option<u64>,
+    last_dynamic_is_bytes: bool,
+}
+
+/// Returns the head layout of the arguments of `signature`.
+const fn signature_layout(signature: &[u8]) -> Option<ArgsLayout> {
+    let params_len = params_len(signature);
+    if params_len == 0 || params_len == signature.len() || signature[signature.len() - 1] != b')' {
+        return None;
+    }
+    args_layout(
+        signature,
+        signature.len() - params_len + 1,
+        signature.len() - 1,
+    )
+}
+
+/// Returns the head layout of the arguments in `s[start..end]`.
+const fn args_layout(s: &[u8], start: usize, end: usize) -> Option<ArgsLayout> {
+    let mut layout = ArgsLayout {
+        words: 0,
+        dynamic: false,
+        bytes_count: 0,
+        bytes_head: None,
+        last_dynamic_head: None,
+        last_dynamic_is_bytes: false,
+    };
+    if trim_start(s, start, end) == end {
+        return Some(layout);
+    }
+
+    let mut i = start;
+    let mut arg_start = start;
+    let mut depth = 0usize;
+    while i <= end {
+        if i < end {
+            match s[i] {
+                b'(' | b'[' => depth += 1,
+                b')' | b']' => {
+                    if depth == 0 {
+                        return None;
+                    }
+                    depth -= 1;
+                }
+                _ => {}
+            }
+        }
+        if i == end || (s[i] == b',' && depth == 0) {
+            let type_start = trim_start(s, arg_start, i);
+            let type_end = match type_end(s, type_start, i) {
+                Some(type_end) if type_end > type_start => type_end,
+                _ => return None,
+            };
+            let (words, dynamic) = match type_words(s, type_start, type_end) {
+                Some(words) => words,
+                None => return None,
+            };
+            let is_bytes = bytes_eq(b"bytes", s, type_start, type_end);
+            if is_bytes {
+                layout.bytes_count += 1;
+                layout.bytes_head = Some(layout.words);
+            }
+            if dynamic {
+                layout.dynamic = true;
+                layout.last_dynamic_head = Some(layout.words);
+                layout.last_dynamic_is_bytes = is_bytes;
+            }
+            layout.words += words;
+            arg_start = i + 1;
+        }
+        i += 1;
+    }
+    if depth != 0 {
+        return None;
+    }
+    Some(layout)
+}
+
+/// Returns the end of the type starting at `s[start]`, before its name or location.
+const fn type_end(s: &[u8], start: usize, end: usize) -> Option<usize> {
+    let mut i = start;
+    if i < end && s[i] == b'(' {
+        let mut depth = 0usize;
+        while i < end {
+            if s[i] == b'(' {
+                depth += 1;
+            } else if s[i] == b')' {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            i += 1;
+        }
+        if i == end {
+            return None;
+        }
+    }
+    while i < end && !s[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    Some(i)
+}
+
+/// Returns the head words of the type `s[start..end]`, and whether it is dynamic.
+const fn type_words(s: &[u8], start: usize, end: usize) -> Option<(u64, bool)> {
+    if s[end - 1] == b']' {
+        let mut bracket = end - 1;
+        while bracket > start && s[bracket] != b'[' {
+            bracket -= 1;
+        }
+        if bracket == start {
+            return None;
+        }
+        let (words, dynamic) = match type_words(s, start, bracket) {
+            Some(words) => words,
+            None => return None,
+        };
+        let len = match parse_u64(s, bracket + 1, end - 1) {
+            Some(len) => len,
+            // `T[]`, or a malformed length.
+            None if bracket + 2 == end => return Some((1, true)),
+            None => return None,
+        };
+        return if dynamic {
+            Some((1, true))
+        } else {
+            Some((words * len, false))
+        };
+    }
+
+    if s[start] == b'(' {
+        if s[end - 1] != b')' {
+            return None;
+        }
+        return match args_layout(s, start + 1, end - 1) {
+            Some(layout) if layout.dynamic => Some((1, true)),
+            Some(layout) => Some((layout.words, false)),
+            None => None,
+        };
+    }
+
+    if bytes_eq(b"bytes", s, start, end) || bytes_eq(b"string", s, start, end) {
+        return Some((1, true));
+    }
+    if bytes_eq(b"address", s, start, end)
+        || bytes_eq(b"bool", s, start, end)
+        || bytes_eq(b"function", s, start, end)
+        || has_size_suffix(s, start, end, b"uint")
+        || has_size_suffix(s, start, end, b"int")
+        || (has_size_suffix(s, start, end, b"bytes") && end - start > 5)
+    {
+        return Some((1, false));
+    }
+    None
+}
+
+/// Returns whether `s[start..end]` is `prefix` followed by optional digits.
+const fn has_size_suffix(s: &[u8], start: usize, end: usize, prefix: &[u8]) -> bool {
+    if end - start < prefix.len() || !bytes_eq(prefix, s, start, start + prefix.len()) {
+        return false;
+    }
+    start + prefix.len() == end || parse_u64(s, start + prefix.len(), end).is_some()
+}
+
+/// Parses the decimal number `s[start..end]`.
+const fn parse_u64(s: &[u8], start: usize, end: usize) -> Option<u64> {
+    if start == end {
+        return None;
+    }
+    let mut value: u64 = 0;
+    let mut i = start;
+    while i < end {
+        if !s[i].is_ascii_digit() {
+            return None;
+        }
+        value = match value.checked_mul(10) {
+            Some(value) => value,
+            None => return None,
+        };
+        value = match value.checked_add((s[i] - b'0') as u64) {
+            Some(value) => value,
+            None => return None,
+        };
+        i += 1;
+    }
+    Some(value)
+}
+
+/// Returns the length of the parameter list of `signature`, from its first `(`.
+const fn params_len(signature: &[u8]) -> usize {
+    let mut i = 0;
+    while i < signature.len() && signature[i] != b'(' {
+        i += 1;
+    }
+    signature.len() - i
+}
+
+/// Returns the index of the first non-whitespace byte of `s[start..end]`, or `end`.
+const fn trim_start(s: &[u8], mut start: usize, end: usize) -> usize {
+    while start < end && s[start].is_ascii_whitespace() {
+        start += 1;
+    }
+    start
+}
+
+/// Returns whether `a` equals `b[start..end]`.
+const fn bytes_eq(a: &[u8], b: &[u8], start: usize, end: usize) -> bool {
+    if a.len() != end - start {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[start + i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
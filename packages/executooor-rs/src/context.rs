@@ -25,6 +25,22 @@ pub fn encode_context(sender: Address, data_index: u64) -> B256 {
     B256::from(b)
 }
 
+/// Decodes a `bytes32` callback context into its `sender` and `data_index`.
+///
+/// Inverse of [`encode_context`]. Returns `None` if the upper 4 bytes are non-zero,
+/// i.e. the data index does not fit in a `u64`.
+pub fn decode_context(context: B256) -> Option<(Address, u64)> {
+    if context[..4] != [0u8; 4] {
+        return None;
+    }
+    let mut index = [0u8; 8];
+    index.copy_from_slice(&context[4..12]);
+    Some((
+        Address::from_slice(&context[12..32]),
+        u64::from_be_bytes(index),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,4 +86,18 @@ mod tests {
         assert_eq!(decoded_index, U256::from(data_index));
         assert_eq!(Address::from(sender_bytes), sender);
     }
+
+    #[test]
+    fn test_decode_context_roundtrip() {
+        let sender = address!("BA12222222228d8Ba445958a75a0704d566BF2C8");
+        let ctx = encode_context(sender, 3);
+        assert_eq!(decode_context(ctx), Some((sender, 3)));
+    }
+
+    #[test]
+    fn test_decode_context_index_overflow() {
+        let mut b = [0u8; 32];
+        b[0] = 1;
+        assert_eq!(decode_context(B256::from(b)), None);
+    }
 }
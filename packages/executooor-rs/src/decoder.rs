@@ -0,0 +1,247 @@
+use std::fmt;
+
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_sol_types::{SolCall, SolValue};
+
+use crate::context::decode_context;
+use crate::encoder::EncodedExec;
+use crate::types::CallbackContext;
+use crate::{callWithPlaceholders4845164670Call, call_g0oyU7oCall, exec_606BaXtCall, Placeholder};
+
+/// Errors returned while decoding executor calldata.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The calldata does not start with a known executor selector.
+    UnknownSelector([u8; 4]),
+    /// The callback context does not fit a `(sender, u64 data_index)` pair.
+    InvalidContext(B256),
+    /// The callback context has a sender but no callback payload was found in the inner call data.
+    CallbackDataNotFound { data_index: u64 },
+    /// ABI decoding failed.
+    Abi(alloy_sol_types::Error),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSelector(selector) => {
+                write!(
+                    f,
+                    "unknown executor selector 0x{}",
+                    alloy_primitives::hex::encode(selector)
+                )
+            }
+            Self::InvalidContext(context) => write!(f, "invalid callback context {context}"),
+            Self::CallbackDataNotFound { data_index } => {
+                write!(f, "no callback data found (data index {data_index})")
+            }
+            Self::Abi(err) => write!(f, "abi decoding failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Abi(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<alloy_sol_types::Error> for DecodeError {
+    fn from(err: alloy_sol_types::Error) -> Self {
+        Self::Abi(err)
+    }
+}
+
+/// A single decoded executor call (`call_g0oyU7o` or `callWithPlaceholders4845164670`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DecodedCall {
+    pub target: Address,
+    pub value: U256,
+    pub context: CallbackContext,
+    pub call_data: Bytes,
+    /// Empty when the call was encoded as `call_g0oyU7o`.
+    pub placeholders: Vec<Placeholder>,
+    /// The calls executed inside the callback, if `context.sender` is set.
+    pub callback: Option<DecodedCallback>,
+}
+
+impl DecodedCall {
+    /// Returns the ETH spent by this call and every call nested in its callback.
+    pub fn total_value(&self) -> U256 {
+        let nested = self.callback.as_ref().map_or(U256::ZERO, |callback| {
+            callback.calls.iter().map(Self::total_value).sum()
+        });
+        self.value + nested
+    }
+}
+
+/// The decoded `abi.encode(bytes[], bytes)` payload passed to a callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DecodedCallback {
+    pub calls: Vec<DecodedCall>,
+    pub return_value: Bytes,
+}
+
+/// A structured view of an [`EncodedExec`] transaction, e.g. to persist submitted batches.
+///
+/// With the `serde` feature, serializes every call's target, value, context, raw call
+/// data, placeholders and nested callback calls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExecPlan {
+    pub to: Address,
+    pub value: U256,
+    pub calls: Vec<DecodedCall>,
+}
+
+impl ExecPlan {
+    /// Decodes the call tree of `exec`.
+    pub fn decode(exec: &EncodedExec) -> Result<Self, DecodeError> {
+        Ok(Self {
+            to: exec.to,
+            value: exec.value,
+            calls: decode_exec(&exec.data)?,
+        })
+    }
+}
+
+/// Decodes a full `exec_606BaXt(bytes[])` calldata into its call tree.
+pub fn decode_exec(data: &[u8]) -> Result<Vec<DecodedCall>, DecodeError> {
+    check_selector(data, exec_606BaXtCall::SELECTOR)?;
+    let exec = exec_606BaXtCall::abi_decode(data)?;
+    exec.data.iter().map(|call| decode_call(call)).collect()
+}
+
+/// Decodes a single executor call, as produced by [`ExecutorEncoder::build_call`].
+///
+/// If the callback context has a non-zero sender, the callback data is read from the
+/// inner call's argument at `data_index` and decoded recursively.
+///
+/// [`ExecutorEncoder::build_call`]: crate::ExecutorEncoder::build_call
+pub fn decode_call(data: &[u8]) -> Result<DecodedCall, DecodeError> {
+    let selector = selector_of(data)?;
+
+    let (target, value, context, call_data, placeholders) =
+        if selector == call_g0oyU7oCall::SELECTOR {
+            let call = call_g0oyU7oCall::abi_decode(data)?;
+            (call.target, call.value, call.context, call.callData, vec![])
+        } else if selector == callWithPlaceholders4845164670Call::SELECTOR {
+            let call = callWithPlaceholders4845164670Call::abi_decode(data)?;
+            (
+                call.target,
+                call.value,
+                call.context,
+                call.callData,
+                call.placeholders,
+            )
+        } else {
+            return Err(DecodeError::UnknownSelector(selector));
+        };
+
+    let (sender, data_index) =
+        decode_context(context).ok_or(DecodeError::InvalidContext(context))?;
+    let context = CallbackContext { sender, data_index };
+
+    let callback = if sender != Address::ZERO {
+        Some(decode_callback(&call_data, data_index)?)
+    } else {
+        None
+    };
+
+    Ok(DecodedCall {
+        target,
+        value,
+        context,
+        call_data,
+        placeholders,
+        callback,
+    })
+}
+
+/// Decodes an `abi.encode(bytes[], bytes)` callback payload, as produced by
+/// [`encode_callback_data`](crate::encoder::encode_callback_data).
+pub fn decode_callback_data(data: &[u8]) -> Result<DecodedCallback, DecodeError> {
+    let (calls, return_value) = <(Vec<Bytes>, Bytes)>::abi_decode_params_validate(data)?;
+    let calls = calls
+        .iter()
+        .map(|call| decode_call(call))
+        .collect::<Result<_, _>>()?;
+    Ok(DecodedCallback {
+        calls,
+        return_value,
+    })
+}
+
+/// Locates and decodes the callback payload forwarded by the inner call.
+///
+/// `data_index` is the position of the `bytes` argument in the *callback* signature,
+/// which often differs from its position in the call that triggers it (e.g. Aave's
+/// `flashLoan` takes `params` as argument 5 while `executeOperation` receives it as
+/// argument 4). The argument at `data_index` is tried first, then every other argument
+/// head of the inner call, keeping the first `bytes` that strictly decodes as
+/// `abi.encode(bytes[], bytes)`.
+fn decode_callback(call_data: &[u8], data_index: u64) -> Result<DecodedCallback, DecodeError> {
+    let hint = usize::try_from(data_index).ok();
+    if let Some(callback) = hint.and_then(|i| try_decode_bytes_arg(call_data, i)) {
+        return Ok(callback);
+    }
+
+    let mut head_end = call_data.len();
+    let mut index = 0;
+    while 4 + 32 * (index + 1) <= head_end {
+        if let Some(offset) = read_word(call_data, 4 + 32 * index) {
+            if Some(index) != hint {
+                if let Some(callback) = try_decode_bytes_arg(call_data, index) {
+                    return Ok(callback);
+                }
+            }
+            // Dynamic arguments are laid out after the head: the smallest offset
+            // seen so far bounds the head.
+            if offset % 32 == 0 && offset > 32 * index {
+                head_end = head_end.min(offset.saturating_add(4));
+            }
+        }
+        index += 1;
+    }
+
+    Err(DecodeError::CallbackDataNotFound { data_index })
+}
+
+/// Reads the dynamic `bytes` argument at `index` of `call_data` and decodes it as a
+/// callback payload, mirroring the Executor's fallback.
+fn try_decode_bytes_arg(call_data: &[u8], index: usize) -> Option<DecodedCallback> {
+    let head = index.checked_mul(32)?.checked_add(4)?;
+    let offset = read_word(call_data, head)?.checked_add(4)?;
+    let length = read_word(call_data, offset)?;
+    let start = offset.checked_add(32)?;
+    let payload = call_data.get(start..start.checked_add(length)?)?;
+
+    decode_callback_data(payload).ok()
+}
+
+/// Reads the 32-byte big-endian word at `at` as a `usize`, if in bounds and small enough.
+pub(crate) fn read_word(data: &[u8], at: usize) -> Option<usize> {
+    let word = data.get(at..at.checked_add(32)?)?;
+    usize::try_from(U256::from_be_slice(word)).ok()
+}
+
+fn selector_of(data: &[u8]) -> Result<[u8; 4], DecodeError> {
+    data.get(..4)
+        .map(|s| s.try_into().unwrap())
+        .ok_or(DecodeError::Abi(alloy_sol_types::Error::Overrun))
+}
+
+fn check_selector(data: &[u8], expected: [u8; 4]) -> Result<(), DecodeError> {
+    let selector = selector_of(data)?;
+    if selector != expected {
+        return Err(DecodeError::UnknownSelector(selector));
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use alloy_primitives::{hex, Address, Bytes};
+use alloy_sol_types::SolValue;
+
+/// Creation code of the `Executor` contract, without constructor arguments.
+///
+/// Compiled from `contracts/Executor.sol` (solc 0.8.25), identical to the bytecode
+/// shipped with the TypeScript packages.
+pub const EXECUTOR_BYTECODE: &[u8] = &hex!(
+    "60a034606657601f61065538819003918201601f19168301916001600160401b03831184841017606a5780849260"
+    "2094604052833981010312606657516001600160a01b03811681036066576080526040516105d6908161007f8239"
+    "608051816103500152f35b5f80fd5b634e487b7160e01b5f52604160045260245ffdfe6040608081526004803610"
+    "15610117575b3615610115575f5c6001600160a01b03811633036100ec576c1fffffffffffffffffffffffe09060"
+    "9b1c1681013501803590825190602092839181830190843782010183528051810183828483019203126100ec5782"
+    "8201519067ffffffffffffffff918281116100ec5783019481603f870112156100ec57848601519561009f61009a"
+    "8861049a565b610408565b96828789838152019160051b830101918483116100ec57838101915b8383106100f057"
+    "505050508301519182116100ec57836100e0926100e694010161051a565b92610564565b81519101f35b5f80fd5b"
+    "82518781116100ec57899161010a8888859487010161051a565b8152019201916100bb565b005b5f3560e01c8015"
+    "6103ad57806001146102e9578060021461019b5763a9059cbb0361001057503660031901126100ec5761014f6103"
+    "f2565b806024353033036100ec575f918291829182916001600160a01b03871615610193575b4781811090821802"
+    "18905af16101866104b2565b901561018e57005b610556565b419150610172565b5060a03660031901126100ec57"
+    "6101b06103f2565b60249267ffffffffffffffff926064604481358681116100ec576101d7903690850161045e56"
+    "5b95608494608435948286116100ec57366023870112156100ec5785013598828a116100ec576005993660248260"
+    "051b890101116100ec57953681900360c21901905f5b888110610231576101158c6044356024358e6104d7565b83"
+    "818e1b83010135838112156100ec578201848101356001600160a01b03811681036100ec57888201356042198336"
+    "03018112156100ec5782019086820135918983116100ec578a019082360382136100ec57825f939284938b519283"
+    "928337810184815203915afa906102a36104b2565b91156102e357908d60a48d846102c86102c08f600199980161"
+    "0505565b928201610505565b946102d7602094859301610505565b01019201015e0161021a565b50610556565b50"
+    "6020806003193601126100ec57813567ffffffffffffffff928382116100ec57366023830112156100ec57810135"
+    "60249061032761009a8261049a565b946024602087848152019260051b850101933685116100ec5760248101925b"
+    "85841061038757877f00000000000000000000000000000000000000000000000000000000000000006001600160"
+    "a01b031633036100ec5761011590610564565b83358381116100ec5787916103a2839288369187010161045e565b"
+    "815201930192610346565b5060803660031901126100ec576103c26103f2565b60643567ffffffffffffffff8111"
+    "6100ec57610115926103e49136910161045e565b9060443590602435906104d7565b600435906001600160a01b03"
+    "821682036100ec57565b6040519190601f01601f1916820167ffffffffffffffff81118382101761042e57604052"
+    "565b634e487b7160e01b5f52604160045260245ffd5b67ffffffffffffffff811161042e57601f01601f19166020"
+    "0190565b81601f820112156100ec5780359061047861009a83610442565b92828452602083830101116100ec5781"
+    "5f926020809301838601378301015290565b67ffffffffffffffff811161042e5760051b60200190565b3d156104"
+    "d2573d906104c661009a83610442565b9182523d5f602084013e565b606090565b91923033036100ec575f928392"
+    "835c95845d602083519301915af16104fa6104b2565b901561018e57505f5d565b3567ffffffffffffffff811681"
+    "036100ec5790565b81601f820112156100ec5780519061053461009a83610442565b928284526020838301011161"
+    "00ec57815f9260208093018386015e8301015290565b80519081156100ec57602001fd5b5f5b815181101561059c"
+    "575f806020808460051b86010151908151910182305af161058d6104b2565b901561018e5750600101610566565b"
+    "505056fea26469706673582212202f653e6d557f45082aa0760d096395c46fae8163b5c3eb1d2f7bb5d820c95d12"
+    "64736f6c63430008190033"
+);
+
+/// Returns the creation code deploying an `Executor` owned by `owner`, i.e. the
+/// [`EXECUTOR_BYTECODE`] followed by the ABI-encoded constructor argument.
+pub fn deploy_bytecode(owner: Address) -> Bytes {
+    [EXECUTOR_BYTECODE, &owner.abi_encode()].concat().into()
+}
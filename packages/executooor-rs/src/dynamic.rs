@@ -0,0 +1,131 @@
+//! Calls to contracts the crate doesn't know, built at runtime from their JSON ABI.
+//!
+//! # Example
+//! ```ignore
+//! let abi = parse_abi(&std::fs::read_to_string("Vault.json")?)?;
+//! let deposit = find_function(&abi, "deposit")?;
+//! // Deposit the executor's whole DAI balance: `assets` is filled at execution time.
+//! let balance = encoder.erc20_balance_of(dai, encoder.address(), 0);
+//! encoder.push_dyn_call(vault, U256::ZERO, deposit, &["0", &receiver], vec![("assets", balance)])?;
+//! ```
+
+use alloy_dyn_abi::{JsonAbiExt, Specifier};
+use alloy_json_abi::{Function, JsonAbi};
+use alloy_primitives::{Address, Bytes, U256};
+
+use crate::encoder::ExecutorEncoder;
+use crate::error::Error;
+use crate::layout::arg_offset;
+use crate::Placeholder;
+
+/// Parses a JSON ABI, as the array found in compiler artifacts.
+pub fn parse_abi(json: &str) -> Result<JsonAbi, Error> {
+    JsonAbi::from_json_str(json).map_err(|err| Error::InvalidAbi(err.to_string()))
+}
+
+/// Returns the function of `abi` called `name`, or with the full signature `name`
+/// (e.g. `"deposit(uint256,address)"`) to pick one of several overloads.
+pub fn find_function<'a>(abi: &'a JsonAbi, name: &str) -> Result<&'a Function, Error> {
+    let unknown = || Error::UnknownFunction(name.to_string());
+    match name.split_once('(') {
+        Some((base, _)) => abi
+            .function(base)
+            .and_then(|overloads| {
+                overloads
+                    .iter()
+                    .find(|function| function.signature() == name)
+            })
+            .ok_or_else(unknown),
+        None => match abi.function(name).map(Vec::as_slice) {
+            Some([function]) => Ok(function),
+            _ => Err(unknown()),
+        },
+    }
+}
+
+/// Encodes a call to `function`, parsing each argument from its string representation.
+///
+/// Arguments use the syntax of [`DynSolType::coerce_str`](alloy_dyn_abi::DynSolType::coerce_str):
+/// decimal or hex numbers (with optional units, e.g. `1.5ether`), hex addresses and bytes,
+/// `true`/`false`, and arrays and tuples as `[a, b]` and `(a, b)`.
+pub fn encode_dyn_call(function: &Function, args: &[&str]) -> Result<Bytes, Error> {
+    if args.len() != function.inputs.len() {
+        return Err(Error::ArgumentCount {
+            expected: function.inputs.len(),
+            found: args.len(),
+        });
+    }
+
+    let values = function
+        .inputs
+        .iter()
+        .zip(args)
+        .enumerate()
+        .map(|(index, (param, arg))| {
+            let invalid = |reason: String| Error::InvalidArgument {
+                name: if param.name.is_empty() {
+                    index.to_string()
+                } else {
+                    param.name.clone()
+                },
+                reason,
+            };
+            param
+                .resolve()
+                .and_then(|ty| ty.coerce_str(arg))
+                .map_err(|err| invalid(err.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let call_data = function
+        .abi_encode_input(&values)
+        .map_err(|err| Error::InvalidAbi(err.to_string()))?;
+    Ok(call_data.into())
+}
+
+impl ExecutorEncoder {
+    /// Pushes a call to `function` on `target`, with arguments parsed from strings as in
+    /// [`encode_dyn_call`].
+    ///
+    /// Each placeholder fills the static argument at its path, e.g. `"assets"` or
+    /// `"params.amountIn"` (see [`arg_offset`]): its `offset` is overwritten.
+    pub fn push_dyn_call(
+        &mut self,
+        target: Address,
+        value: U256,
+        function: &Function,
+        args: &[&str],
+        placeholders: Vec<(&str, Placeholder)>,
+    ) -> Result<&mut Self, Error> {
+        let call_data = encode_dyn_call(function, args)?;
+        let placeholders = placeholders
+            .into_iter()
+            .map(|(path, mut placeholder)| {
+                placeholder.offset = arg_offset(function, &call_data, path)?;
+                Ok(placeholder)
+            })
+            .collect::<Result<_, Error>>()?;
+        self.try_push_call(target, value, call_data, None, placeholders)
+    }
+
+    /// Creates a `Placeholder` that reads the view `function` on `to`, with arguments
+    /// parsed from strings, and injects the `word`-th 32-byte word of its return data.
+    ///
+    /// This is the dynamic counterpart of [`view_placeholder`](Self::view_placeholder);
+    /// `offset` is usually set by [`push_dyn_call`](Self::push_dyn_call).
+    pub fn dyn_view_placeholder(
+        &self,
+        to: Address,
+        function: &Function,
+        args: &[&str],
+        word: u64,
+    ) -> Result<Placeholder, Error> {
+        Ok(Placeholder {
+            to,
+            data: encode_dyn_call(function, args)?,
+            offset: 0,
+            length: 32,
+            resOffset: 32 * word,
+        })
+    }
+}
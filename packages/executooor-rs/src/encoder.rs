@@ -1,12 +1,19 @@
+use alloy_json_abi::Function;
 use alloy_primitives::{Address, Bytes, U256};
-use alloy_sol_types::SolCall;
+use alloy_sol_types::{JsonAbiExt, SolCall};
 
+use crate::abi;
+use crate::callbacks::trailing_bytes_head;
 use crate::context::encode_context;
+use crate::error::Error;
+use crate::layout::sol_arg_offset;
+use crate::signatures;
 use crate::types::CallbackContext;
 use crate::{callWithPlaceholders4845164670Call, call_g0oyU7oCall, exec_606BaXtCall, Placeholder};
 
 /// The encoded transaction data ready to be sent.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EncodedExec {
     pub to: Address,
     pub data: Bytes,
@@ -27,7 +34,18 @@ pub struct EncodedExec {
 pub struct ExecutorEncoder {
     address: Address,
     calls: Vec<Bytes>,
+    /// Value of each call in `calls`, including the calls nested in its callback,
+    /// summing to `total_value`.
+    values: Vec<U256>,
     total_value: U256,
+    /// Value of calls drained by [`flush`](Self::flush) or built in a sub-encoder,
+    /// added to the next call pushed with callback calls.
+    nested_value: U256,
+    /// Off-chain metadata, one entry per call in `calls`.
+    metadata: Vec<CallMetadata>,
+    /// Metadata of calls drained by [`flush`](Self::flush) or built in a sub-encoder,
+    /// attached to the next call pushed with callback calls.
+    callback_metadata: Vec<CallMetadata>,
 }
 
 impl ExecutorEncoder {
@@ -35,7 +53,11 @@ impl ExecutorEncoder {
         Self {
             address,
             calls: Vec::new(),
+            values: Vec::new(),
             total_value: U256::ZERO,
+            nested_value: U256::ZERO,
+            metadata: Vec::new(),
+            callback_metadata: Vec::new(),
         }
     }
 
@@ -48,6 +70,10 @@ impl ExecutorEncoder {
     ///
     /// If `placeholders` is non-empty, encodes as `callWithPlaceholders4845164670`.
     /// Otherwise, encodes as `call_g0oyU7o`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a placeholder is invalid — see [`try_build_call`](Self::try_build_call).
     pub fn build_call(
         target: Address,
         value: U256,
@@ -55,34 +81,51 @@ impl ExecutorEncoder {
         context: Option<&CallbackContext>,
         placeholders: Vec<Placeholder>,
     ) -> Bytes {
+        Self::try_build_call(target, value, call_data, context, placeholders)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`build_call`](Self::build_call).
+    ///
+    /// Placeholders are copied with an unchecked `mcopy` onchain, so they are validated here:
+    /// - each must fit in `call_data` ([`Error::PlaceholderOutOfBounds`]);
+    /// - no two may write the same bytes ([`Error::OverlappingPlaceholders`]);
+    /// - when the source function is known to the crate and returns static data, the word
+    ///   read must fit in it ([`Error::PlaceholderResultOutOfBounds`]).
+    pub fn try_build_call(
+        target: Address,
+        value: U256,
+        call_data: Bytes,
+        context: Option<&CallbackContext>,
+        placeholders: Vec<Placeholder>,
+    ) -> Result<Bytes, Error> {
+        check_placeholders(&call_data, &placeholders)?;
+
         let default_ctx = CallbackContext::default();
         let ctx = context.unwrap_or(&default_ctx);
         let encoded_context = encode_context(ctx.sender, ctx.data_index);
 
-        if !placeholders.is_empty() {
-            callWithPlaceholders4845164670Call {
-                target,
-                value,
-                context: encoded_context,
-                callData: call_data,
-                placeholders,
-            }
-            .abi_encode()
-            .into()
+        let selector = if placeholders.is_empty() {
+            call_g0oyU7oCall::SELECTOR
         } else {
-            call_g0oyU7oCall {
-                target,
-                value,
-                context: encoded_context,
-                callData: call_data,
-            }
-            .abi_encode()
-            .into()
-        }
+            callWithPlaceholders4845164670Call::SELECTOR
+        };
+        Ok(abi::encode_call(
+            selector,
+            target,
+            value,
+            encoded_context,
+            &call_data,
+            &placeholders,
+        ))
     }
 
     /// Pushes an encoded call onto the internal call list.
     /// Returns `&mut Self` for method chaining.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a placeholder is invalid — see [`try_build_call`](Self::try_build_call).
     pub fn push_call(
         &mut self,
         target: Address,
@@ -91,37 +134,581 @@ impl ExecutorEncoder {
         context: Option<&CallbackContext>,
         placeholders: Vec<Placeholder>,
     ) -> &mut Self {
-        self.total_value += value;
-        self.calls.push(Self::build_call(
+        self.try_push_call(target, value, call_data, context, placeholders)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`push_call`](Self::push_call).
+    ///
+    /// Returns an error if a placeholder is invalid — see
+    /// [`try_build_call`](Self::try_build_call).
+    pub fn try_push_call(
+        &mut self,
+        target: Address,
+        value: U256,
+        call_data: Bytes,
+        context: Option<&CallbackContext>,
+        placeholders: Vec<Placeholder>,
+    ) -> Result<&mut Self, Error> {
+        self.try_insert_call(
+            self.calls.len(),
             target,
             value,
             call_data,
             context,
             placeholders,
-        ));
+        )
+    }
+
+    /// Inserts an encoded call at position `index`, shifting all calls after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len()` or a placeholder is invalid — see
+    /// [`try_insert_call`](Self::try_insert_call).
+    pub fn insert_call(
+        &mut self,
+        index: usize,
+        target: Address,
+        value: U256,
+        call_data: Bytes,
+        context: Option<&CallbackContext>,
+        placeholders: Vec<Placeholder>,
+    ) -> &mut Self {
+        self.try_insert_call(index, target, value, call_data, context, placeholders)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`insert_call`](Self::insert_call).
+    ///
+    /// Returns [`Error::CallIndexOutOfBounds`] if `index > len()`, or an error if a
+    /// placeholder is invalid — see [`try_build_call`](Self::try_build_call).
+    pub fn try_insert_call(
+        &mut self,
+        index: usize,
+        target: Address,
+        value: U256,
+        call_data: Bytes,
+        context: Option<&CallbackContext>,
+        placeholders: Vec<Placeholder>,
+    ) -> Result<&mut Self, Error> {
+        if index > self.calls.len() {
+            return Err(Error::CallIndexOutOfBounds {
+                index,
+                len: self.calls.len(),
+            });
+        }
+        let call = Self::try_build_call(target, value, call_data, context, placeholders)?;
+        self.insert_encoded(index, call, value, NestedCalls::default());
+        Ok(self)
+    }
+
+    /// Pushes a call to `target` that triggers a callback receiving `callback_calls`
+    /// and `return_value`, i.e. [`encode_callback_data`] as its `bytes` argument.
+    ///
+    /// `call` must be encoded with an empty `bytes` in place of the callback data, and
+    /// that argument must be the last dynamic one, so its encoding ends the call data
+    /// (true of every flash loan and Morpho Blue callback in this crate). The callback
+    /// payload is then written once, directly into the executor call, instead of being
+    /// encoded, copied into `call` and copied again into the executor call.
+    ///
+    /// Unless `callback_calls` is empty, the value and metadata of the calls drained by
+    /// [`flush`](Self::flush) or [`nest_callback`](Self::nest_callback) since the last
+    /// callback call are attached to this call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `call` doesn't end with an empty `bytes`: see
+    /// [`try_push_callback_call`](Self::try_push_callback_call).
+    ///
+    /// # Example
+    /// ```ignore
+    /// let call = flashLoanCall { asset, amount, data: Bytes::new() };
+    /// encoder.push_callback_call(morpho_blue, U256::ZERO, call, &ctx, calls, Bytes::new());
+    /// ```
+    pub fn push_callback_call<C: SolCall>(
+        &mut self,
+        target: Address,
+        value: U256,
+        call: C,
+        context: &CallbackContext,
+        callback_calls: Vec<Bytes>,
+        return_value: Bytes,
+    ) -> &mut Self {
+        self.try_push_callback_call(target, value, call, context, callback_calls, return_value)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`push_callback_call`](Self::push_callback_call).
+    ///
+    /// Returns [`Error::InvalidCallbackCall`] unless the last dynamic argument of `C` is a
+    /// `bytes`, and `call` encodes it as empty at the end of its call data.
+    pub fn try_push_callback_call<C: SolCall>(
+        &mut self,
+        target: Address,
+        value: U256,
+        call: C,
+        context: &CallbackContext,
+        callback_calls: Vec<Bytes>,
+        return_value: Bytes,
+    ) -> Result<&mut Self, Error> {
+        let call = Self::try_build_callback_call(
+            target,
+            value,
+            call,
+            context,
+            &callback_calls,
+            &return_value,
+        )?;
+        let nested = self.take_nested_into(&callback_calls);
+        self.insert_encoded(self.calls.len(), call, value, nested);
+        Ok(self)
+    }
+
+    /// Encodes the executor call of [`try_push_callback_call`](Self::try_push_callback_call).
+    pub(crate) fn try_build_callback_call<C: SolCall>(
+        target: Address,
+        value: U256,
+        call: C,
+        context: &CallbackContext,
+        callback_calls: &[Bytes],
+        return_value: &[u8],
+    ) -> Result<Bytes, Error> {
+        let encoded = call.abi_encode();
+        let head = callback_call_head::<C>(&encoded)
+            .ok_or_else(|| Error::InvalidCallbackCall(C::SIGNATURE.to_string()))?;
+        Ok(abi::encode_callback_call(
+            target,
+            value,
+            encode_context(context.sender, context.data_index),
+            head,
+            callback_calls,
+            return_value,
+        ))
+    }
+
+    /// Takes the value and metadata of the calls drained by [`flush`](Self::flush) or
+    /// [`nest_callback`](Self::nest_callback), to attach them to the call nesting
+    /// `callback_calls`: nothing is nested in an empty callback.
+    pub(crate) fn take_nested_into(&mut self, callback_calls: &[Bytes]) -> NestedCalls {
+        if callback_calls.is_empty() {
+            return NestedCalls::default();
+        }
+        NestedCalls {
+            value: std::mem::take(&mut self.nested_value),
+            metadata: std::mem::take(&mut self.callback_metadata),
+        }
+    }
+
+    /// Inserts the encoded `call` at `index`, with the calls `nested` in its callback.
+    pub(crate) fn insert_encoded(
+        &mut self,
+        index: usize,
+        call: Bytes,
+        value: U256,
+        nested: NestedCalls,
+    ) {
+        let value = value + nested.value;
+        self.total_value += value;
+        self.calls.insert(index, call);
+        self.values.insert(index, value);
+        self.metadata.insert(
+            index,
+            CallMetadata {
+                callback: nested.metadata,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Removes and returns the call at `index`, along with its value and metadata,
+    /// including those of the calls nested in its callback.
+    ///
+    /// Returns [`Error::CallIndexOutOfBounds`] if `index >= len()`.
+    pub fn remove_call(&mut self, index: usize) -> Result<Bytes, Error> {
+        self.check_index(index)?;
+        self.total_value -= self.values.remove(index);
+        self.metadata.remove(index);
+        Ok(self.calls.remove(index))
+    }
+
+    /// Moves the call at `from` to position `to`, shifting the calls in between.
+    ///
+    /// E.g. to run an approval before an existing call, push it then move it in place.
+    /// Returns [`Error::CallIndexOutOfBounds`] if either index is `>= len()`.
+    pub fn move_call(&mut self, from: usize, to: usize) -> Result<&mut Self, Error> {
+        self.check_index(from)?;
+        self.check_index(to)?;
+        let call = self.calls.remove(from);
+        self.calls.insert(to, call);
+        let value = self.values.remove(from);
+        self.values.insert(to, value);
+        let metadata = self.metadata.remove(from);
+        self.metadata.insert(to, metadata);
+        Ok(self)
+    }
+
+    /// Swaps the calls at positions `a` and `b`.
+    ///
+    /// Returns [`Error::CallIndexOutOfBounds`] if either index is `>= len()`.
+    pub fn swap_calls(&mut self, a: usize, b: usize) -> Result<&mut Self, Error> {
+        self.check_index(a)?;
+        self.check_index(b)?;
+        self.calls.swap(a, b);
+        self.values.swap(a, b);
+        self.metadata.swap(a, b);
+        Ok(self)
+    }
+
+    /// Returns the number of pending calls.
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Returns `true` if no call is pending.
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Returns the pending encoded calls, in order.
+    pub fn calls(&self) -> &[Bytes] {
+        &self.calls
+    }
+
+    /// Snapshots the encoder state, to be restored with [`rollback`](Self::rollback).
+    ///
+    /// # Example
+    /// ```ignore
+    /// let checkpoint = encoder.checkpoint();
+    /// encoder.uni_v3_exact_input(router, path, amount_in, min_out, deadline, None);
+    /// if !profitable(&encoder) {
+    ///     encoder.rollback(checkpoint);
+    /// }
+    /// ```
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            calls: self.calls.clone(),
+            values: self.values.clone(),
+            total_value: self.total_value,
+            nested_value: self.nested_value,
+            metadata: self.metadata.clone(),
+            callback_metadata: self.callback_metadata.clone(),
+        }
+    }
+
+    /// Restores the state captured by [`checkpoint`](Self::checkpoint), undoing every
+    /// call pushed, inserted, removed, reordered or flushed since.
+    pub fn rollback(&mut self, checkpoint: Checkpoint) -> &mut Self {
+        self.calls = checkpoint.calls;
+        self.values = checkpoint.values;
+        self.total_value = checkpoint.total_value;
+        self.nested_value = checkpoint.nested_value;
+        self.metadata = checkpoint.metadata;
+        self.callback_metadata = checkpoint.callback_metadata;
         self
     }
 
-    /// Drains and returns all accumulated calls, resetting internal state.
+    fn check_index(&self, index: usize) -> Result<(), Error> {
+        if index >= self.calls.len() {
+            return Err(Error::CallIndexOutOfBounds {
+                index,
+                len: self.calls.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Labels the last call of the list, e.g. `"repay balancer loan"`.
+    ///
+    /// Labels are kept off-chain in [`metadata`](Self::metadata) and never encoded.
+    /// Does nothing if the list is empty.
+    pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        if let Some(metadata) = self.metadata.last_mut() {
+            metadata.label = Some(label.into());
+        }
+        self
+    }
+
+    /// Attaches a `key = value` tag to the last call of the list, e.g. `("asset", "DAI")`.
+    ///
+    /// Does nothing if the list is empty.
+    pub fn tag(&mut self, key: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        if let Some(metadata) = self.metadata.last_mut() {
+            metadata.tags.push((key.into(), value.into()));
+        }
+        self
+    }
+
+    /// Returns the metadata of the pending calls, in call order.
+    pub fn metadata(&self) -> &[CallMetadata] {
+        &self.metadata
+    }
+
+    /// Returns the metadata of the call at `index`, to annotate calls other than the last.
+    pub fn metadata_mut(&mut self, index: usize) -> Option<&mut CallMetadata> {
+        self.metadata.get_mut(index)
+    }
+
+    /// Creates a `Placeholder` that reads the view `call` on `to` and injects the
+    /// `word`-th 32-byte word of its return data at `offset` in the call data.
+    ///
+    /// This is the primitive behind the typed sources such as
+    /// [`erc20_balance_of`](Self::erc20_balance_of).
+    pub fn view_placeholder<C: SolCall>(
+        &self,
+        to: Address,
+        call: C,
+        word: u64,
+        offset: u64,
+    ) -> Placeholder {
+        Placeholder {
+            to,
+            data: call.abi_encode().into(),
+            offset,
+            length: 32,
+            resOffset: 32 * word,
+        }
+    }
+
+    /// Pushes a typed `sol!` call to `target`, with no value, context or placeholders.
+    ///
+    /// # Example
+    /// ```ignore
+    /// sol! {
+    ///     function harvest(address receiver) external;
+    /// }
+    /// encoder.push_sol_call(vault, harvestCall { receiver });
+    /// ```
+    pub fn push_sol_call<C: SolCall>(&mut self, target: Address, call: C) -> &mut Self {
+        self.push_sol_call_with(target, U256::ZERO, None, vec![], call)
+    }
+
+    /// Pushes a typed `sol!` call to `target` with an explicit value, callback context
+    /// and placeholders.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a placeholder is invalid — see [`try_build_call`](Self::try_build_call).
+    pub fn push_sol_call_with<C: SolCall>(
+        &mut self,
+        target: Address,
+        value: U256,
+        context: Option<&CallbackContext>,
+        placeholders: Vec<Placeholder>,
+        call: C,
+    ) -> &mut Self {
+        self.push_call(
+            target,
+            value,
+            call.abi_encode().into(),
+            context,
+            placeholders,
+        )
+    }
+
+    /// Pushes a typed `sol!` call whose static argument at `path` is filled at execution
+    /// time by `placeholder`.
+    ///
+    /// The placeholder's `offset` is computed from the call's ABI with
+    /// [`sol_arg_offset`](crate::layout::sol_arg_offset), so the call must be declared
+    /// with `#[sol(abi)]`.
+    pub fn push_sol_call_with_placeholder<C>(
+        &mut self,
+        target: Address,
+        call: C,
+        path: &str,
+        mut placeholder: Placeholder,
+    ) -> Result<&mut Self, Error>
+    where
+        C: SolCall + JsonAbiExt<Abi = Function>,
+    {
+        placeholder.offset = sol_arg_offset(&call, path)?;
+        self.try_push_call(
+            target,
+            U256::ZERO,
+            call.abi_encode().into(),
+            None,
+            vec![placeholder],
+        )
+    }
+
+    /// Returns the ETH spent by the pending batch, including calls nested in callbacks.
+    ///
+    /// This is the minimum `msg.value` [`encode_exec`](Self::encode_exec) will request.
+    pub fn value(&self) -> U256 {
+        self.total_value + self.nested_value
+    }
+
+    /// Drains and returns all accumulated calls.
+    ///
+    /// Flushed calls are typically passed back as callback calls, so their value is kept
+    /// and still accounted for by [`value`](Self::value) and [`encode_exec`](Self::encode_exec).
+    /// Their value and metadata are attached to the next call pushed with callback calls.
+    ///
+    /// Flushing is not discarding: until that callback call is pushed, the ETH of the
+    /// flushed calls is still sent by [`encode_exec`](Self::encode_exec).
+    /// Use [`clear`](Self::clear) to drop calls instead.
     pub fn flush(&mut self) -> Vec<Bytes> {
-        self.total_value = U256::ZERO;
+        self.nested_value += std::mem::take(&mut self.total_value);
+        self.callback_metadata.append(&mut self.metadata);
+        self.values.clear();
         std::mem::take(&mut self.calls)
     }
 
+    /// Drops all accumulated calls, along with the value and metadata of previously
+    /// flushed calls not yet attached to a callback call.
+    pub fn clear(&mut self) -> &mut Self {
+        self.calls.clear();
+        self.values.clear();
+        self.total_value = U256::ZERO;
+        self.nested_value = U256::ZERO;
+        self.metadata.clear();
+        self.callback_metadata.clear();
+        self
+    }
+
+    /// Runs `build` against a fresh encoder bound to the same executor address
+    /// and returns the calls it pushed.
+    ///
+    /// Used by the `*_with` callback methods so callers never have to `flush()`
+    /// the outer encoder mid-chain. The value and metadata of the inner calls are carried up.
+    pub fn build_callback(&mut self, build: impl FnOnce(&mut ExecutorEncoder)) -> Vec<Bytes> {
+        let mut inner = ExecutorEncoder::new(self.address);
+        build(&mut inner);
+        self.nest_callback(&mut inner)
+    }
+
+    /// Nests the calls `build` pushes onto a sub-encoder in the callback of the call
+    /// `push` pushes, dropping them along with their value and metadata if either fails.
+    pub(crate) fn try_push_with_callback<B, P>(
+        &mut self,
+        build: B,
+        push: P,
+    ) -> Result<&mut Self, Error>
+    where
+        B: FnOnce(&mut ExecutorEncoder) -> Result<(), Error>,
+        P: FnOnce(&mut ExecutorEncoder, Vec<Bytes>) -> Result<&mut ExecutorEncoder, Error>,
+    {
+        let mut inner = ExecutorEncoder::new(self.address);
+        build(&mut inner)?;
+        let (nested_value, nested_len) = (self.nested_value, self.callback_metadata.len());
+        let callback_calls = self.nest_callback(&mut inner);
+        if let Err(err) = push(self, callback_calls).map(|_| ()) {
+            self.nested_value = nested_value;
+            self.callback_metadata.truncate(nested_len);
+            return Err(err);
+        }
+        Ok(self)
+    }
+
+    /// Moves all calls of `other` to the end of this encoder, leaving `other` empty.
+    ///
+    /// Value and metadata are carried over, including those of calls `other` flushed
+    /// for a callback.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is bound to a different executor address.
+    pub fn append(&mut self, other: &mut ExecutorEncoder) -> &mut Self {
+        self.try_append(other).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`append`](Self::append).
+    ///
+    /// Returns [`Error::ExecutorMismatch`] and leaves both encoders untouched if `other`
+    /// is bound to a different executor address.
+    pub fn try_append(&mut self, other: &mut ExecutorEncoder) -> Result<&mut Self, Error> {
+        self.check_executor(other)?;
+        self.calls.append(&mut other.calls);
+        self.values.append(&mut other.values);
+        self.metadata.append(&mut other.metadata);
+        self.callback_metadata.append(&mut other.callback_metadata);
+        self.total_value += std::mem::take(&mut other.total_value);
+        self.nested_value += std::mem::take(&mut other.nested_value);
+        Ok(self)
+    }
+
+    /// Drains the calls of `other` to be passed as the callback calls of a call pushed
+    /// on this encoder, leaving `other` empty.
+    ///
+    /// Like [`build_callback`](Self::build_callback), for sub-plans built separately:
+    /// the value and metadata of the nested calls are carried up.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut liquidation = liquidate(executor, &market);
+    /// let callback = encoder.nest_callback(&mut liquidation);
+    /// encoder.blue_flash_loan(morpho, dai, amount, Some(callback));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is bound to a different executor address.
+    pub fn nest_callback(&mut self, other: &mut ExecutorEncoder) -> Vec<Bytes> {
+        self.try_nest_callback(other)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`nest_callback`](Self::nest_callback).
+    ///
+    /// Returns [`Error::ExecutorMismatch`] and leaves both encoders untouched if `other`
+    /// is bound to a different executor address.
+    pub fn try_nest_callback(&mut self, other: &mut ExecutorEncoder) -> Result<Vec<Bytes>, Error> {
+        self.check_executor(other)?;
+        let calls = other.flush();
+        self.nested_value += std::mem::take(&mut other.nested_value);
+        self.callback_metadata.append(&mut other.callback_metadata);
+        Ok(calls)
+    }
+
+    fn check_executor(&self, other: &ExecutorEncoder) -> Result<(), Error> {
+        if other.address != self.address {
+            return Err(Error::ExecutorMismatch {
+                expected: self.address,
+                found: other.address,
+            });
+        }
+        Ok(())
+    }
+
     /// Transfers ETH to the recipient via the Executor contract.
     ///
     /// # Panics
     ///
     /// Panics if `recipient` is `Address::ZERO` — use [`tip`](Self::tip) instead.
     pub fn transfer(&mut self, recipient: Address, amount: U256) -> &mut Self {
-        assert!(
-            recipient != Address::ZERO,
-            "recipient should not be zero: use tip() instead"
-        );
+        self.try_transfer(recipient, amount)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`transfer`](Self::transfer).
+    ///
+    /// Returns [`Error::ZeroRecipient`] if `recipient` is `Address::ZERO`.
+    pub fn try_transfer(&mut self, recipient: Address, amount: U256) -> Result<&mut Self, Error> {
+        if recipient == Address::ZERO {
+            return Err(Error::ZeroRecipient);
+        }
         let call_data = crate::executor_sol::transferCall { recipient, amount }
             .abi_encode()
             .into();
-        self.push_call(self.address(), U256::ZERO, call_data, None, vec![])
+        Ok(self.push_call(self.address(), U256::ZERO, call_data, None, vec![]))
+    }
+
+    /// Transfers the executor's whole ETH balance to `recipient`, as `transfer` is capped
+    /// to the balance onchain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `recipient` is `Address::ZERO` — use [`tip`](Self::tip) instead.
+    pub fn sweep_eth(&mut self, recipient: Address) -> &mut Self {
+        self.transfer(recipient, U256::MAX)
+    }
+
+    /// Fallible version of [`sweep_eth`](Self::sweep_eth).
+    ///
+    /// Returns [`Error::ZeroRecipient`] if `recipient` is `Address::ZERO`.
+    pub fn try_sweep_eth(&mut self, recipient: Address) -> Result<&mut Self, Error> {
+        self.try_transfer(recipient, U256::MAX)
     }
 
     /// Sends ETH to `block.coinbase` (miner/validator tip).
@@ -137,18 +724,113 @@ impl ExecutorEncoder {
 
     /// Encodes the full `exec_606BaXt(bytes[])` transaction.
     ///
-    /// Consumes all accumulated calls (equivalent to calling [`flush`](Self::flush)).
+    /// Consumes all accumulated calls. The transaction value is [`value`](Self::value)
+    /// plus `extra_value`, so it always covers the ETH spent inside callbacks.
     /// The encoder is reset and ready for the next batch after this call.
     pub fn encode_exec(&mut self, extra_value: U256) -> EncodedExec {
-        let value = self.total_value + extra_value;
-        let calls = self.flush();
-        let data: Bytes = exec_606BaXtCall { data: calls }.abi_encode().into();
+        let value = self.value() + extra_value;
+        let calls = std::mem::take(&mut self.calls);
+        self.clear();
+        let data = abi::encode_bytes_array_call(exec_606BaXtCall::SELECTOR, &calls);
         EncodedExec {
             to: self.address,
             data,
             value,
         }
     }
+
+    /// Like [`encode_exec`](Self::encode_exec), also returning the metadata of the encoded
+    /// calls, in call order.
+    pub fn encode_exec_with_metadata(
+        &mut self,
+        extra_value: U256,
+    ) -> (EncodedExec, Vec<CallMetadata>) {
+        let metadata = std::mem::take(&mut self.metadata);
+        (self.encode_exec(extra_value), metadata)
+    }
+}
+
+/// A snapshot of an [`ExecutorEncoder`] taken by [`ExecutorEncoder::checkpoint`].
+///
+/// Encoded calls are reference-counted, so taking a checkpoint does not copy call data.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    calls: Vec<Bytes>,
+    values: Vec<U256>,
+    total_value: U256,
+    nested_value: U256,
+    metadata: Vec<CallMetadata>,
+    callback_metadata: Vec<CallMetadata>,
+}
+
+/// Value and metadata of the calls nested in a callback.
+#[derive(Debug, Default)]
+pub(crate) struct NestedCalls {
+    value: U256,
+    metadata: Vec<CallMetadata>,
+}
+
+/// Off-chain annotations of a call, never encoded onchain.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallMetadata {
+    pub label: Option<String>,
+    pub tags: Vec<(String, String)>,
+    /// Metadata of the calls nested in this call's callback, in order.
+    ///
+    /// Calls appended by the encoder itself (e.g. flash loan repayments) come last
+    /// and have no entry.
+    pub callback: Vec<CallMetadata>,
+}
+
+/// Returns `encoded` without the zero length word of its trailing empty `bytes`, if the
+/// head offset of the last dynamic argument of `C` points to it.
+fn callback_call_head<C: SolCall>(encoded: &[u8]) -> Option<&[u8]> {
+    let head_word = trailing_bytes_head(C::SIGNATURE)? as usize;
+    let len = encoded.len().checked_sub(4 + 32)?;
+    let (head, length) = encoded.split_at(4 + len);
+    let offset = encoded.get(4 + 32 * head_word..4 + 32 * (head_word + 1))?;
+    (length == [0; 32] && U256::from_be_slice(offset) == U256::from(len)).then_some(head)
+}
+
+/// Checks that every placeholder writes within `call_data`, that no two overlap and that
+/// each reads within the static return data of its source, when known.
+fn check_placeholders(call_data: &[u8], placeholders: &[Placeholder]) -> Result<(), Error> {
+    let mut ranges = Vec::with_capacity(placeholders.len());
+    for (index, placeholder) in placeholders.iter().enumerate() {
+        let end = placeholder.offset.saturating_add(placeholder.length);
+        if end > call_data.len() as u64 {
+            return Err(Error::PlaceholderOutOfBounds {
+                index,
+                end,
+                len: call_data.len(),
+            });
+        }
+
+        if let Some(other) = ranges
+            .iter()
+            .position(|&(start, other_end)| placeholder.offset < other_end && start < end)
+        {
+            return Err(Error::OverlappingPlaceholders(other, index));
+        }
+        ranges.push((placeholder.offset, end));
+
+        let return_size = placeholder
+            .data
+            .get(..4)
+            .and_then(signatures::max_return_size);
+        if let Some(return_size) = return_size {
+            let res_end = placeholder.resOffset.saturating_add(placeholder.length);
+            if res_end > return_size as u64 {
+                return Err(Error::PlaceholderResultOutOfBounds {
+                    index,
+                    end: res_end,
+                    return_size,
+                });
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Encodes callback data as `abi.encode(bytes[], bytes)`.
@@ -157,6 +839,5 @@ impl ExecutorEncoder {
 /// the first element is an array of calls to execute inside the callback,
 /// the second element is a return value (often `0x` or a hash).
 pub fn encode_callback_data(calls: Vec<Bytes>, return_value: Bytes) -> Bytes {
-    use alloy_sol_types::SolValue;
-    (calls, return_value).abi_encode_params().into()
+    abi::encode_callback_data(&calls, &return_value)
 }
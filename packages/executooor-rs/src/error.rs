@@ -0,0 +1,141 @@
+use std::fmt;
+
+use alloy_primitives::Address;
+
+use crate::decoder::DecodeError;
+
+/// Errors returned by the fallible `try_*` builder methods.
+#[derive(Debug)]
+pub enum Error {
+    /// `transfer` was called with the zero address: use `tip` instead.
+    ZeroRecipient,
+    /// A UniswapV3 path is shorter than the 20-byte input token address.
+    PathTooShort { len: usize },
+    /// A flash loan requests no asset, or only zero amounts.
+    EmptyFlashLoan,
+    /// The flash loan asset at `index` is the zero address but has a non-zero amount.
+    ZeroAsset { index: usize },
+    /// The same asset is requested more than once in a single flash loan.
+    DuplicateAsset(Address),
+    /// An argument path does not match the function ABI or its encoded call data.
+    InvalidArgumentPath(String),
+    /// An argument path points to a dynamic value, which a placeholder cannot overwrite.
+    DynamicArgument(String),
+    /// Placeholder `index` writes up to byte `end`, past the end of the call data.
+    PlaceholderOutOfBounds { index: usize, end: u64, len: usize },
+    /// Two placeholders (by index) write overlapping call data bytes.
+    OverlappingPlaceholders(usize, usize),
+    /// Placeholder `index` reads up to byte `end`, past the static return data of its source.
+    PlaceholderResultOutOfBounds {
+        index: usize,
+        end: u64,
+        return_size: usize,
+    },
+    /// A call index is past the end of the encoder's call list.
+    CallIndexOutOfBounds { index: usize, len: usize },
+    /// Two encoders built for different executor contracts cannot be merged.
+    ExecutorMismatch { expected: Address, found: Address },
+    /// A strategy file could not be parsed.
+    InvalidStrategy(String),
+    /// A JSON ABI could not be parsed.
+    InvalidAbi(String),
+    /// A JSON ABI has no function with this name or signature, or several overloads.
+    UnknownFunction(String),
+    /// A dynamic call received a wrong number of arguments.
+    ArgumentCount { expected: usize, found: usize },
+    /// A dynamic call argument could not be parsed as its ABI type.
+    InvalidArgument { name: String, reason: String },
+    /// A callback is neither known to the crate nor given by its full signature.
+    UnknownCallback(String),
+    /// A callback signature doesn't have exactly one top-level `bytes` argument.
+    InvalidCallbackSignature(String),
+    /// A callback call doesn't end with its empty `bytes` callback data, by signature.
+    InvalidCallbackCall(String),
+    /// Decoding executor calldata failed.
+    Decode(DecodeError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroRecipient => write!(f, "recipient should not be zero: use tip() instead"),
+            Self::PathTooShort { len } => {
+                write!(f, "path must be at least 20 bytes, got {len}")
+            }
+            Self::EmptyFlashLoan => write!(f, "flash loan requests no asset"),
+            Self::ZeroAsset { index } => {
+                write!(f, "flash loan asset {index} is the zero address")
+            }
+            Self::DuplicateAsset(asset) => {
+                write!(f, "flash loan requests {asset} more than once")
+            }
+            Self::InvalidArgumentPath(path) => write!(f, "invalid argument path `{path}`"),
+            Self::DynamicArgument(path) => write!(f, "argument `{path}` is dynamic"),
+            Self::PlaceholderOutOfBounds { index, end, len } => write!(
+                f,
+                "placeholder {index} writes up to byte {end} of {len}-byte call data"
+            ),
+            Self::OverlappingPlaceholders(first, second) => {
+                write!(f, "placeholders {first} and {second} overlap")
+            }
+            Self::PlaceholderResultOutOfBounds {
+                index,
+                end,
+                return_size,
+            } => write!(
+                f,
+                "placeholder {index} reads up to byte {end} of {return_size}-byte return data"
+            ),
+            Self::CallIndexOutOfBounds { index, len } => {
+                write!(f, "call index {index} out of bounds for {len} calls")
+            }
+            Self::ExecutorMismatch { expected, found } => {
+                write!(
+                    f,
+                    "cannot merge calls for executor {found} into executor {expected}"
+                )
+            }
+            Self::InvalidStrategy(reason) => write!(f, "invalid strategy: {reason}"),
+            Self::InvalidAbi(reason) => write!(f, "invalid ABI: {reason}"),
+            Self::UnknownFunction(name) => {
+                write!(
+                    f,
+                    "no single function `{name}` in ABI: use its full signature"
+                )
+            }
+            Self::ArgumentCount { expected, found } => {
+                write!(f, "expected {expected} arguments, got {found}")
+            }
+            Self::InvalidArgument { name, reason } => {
+                write!(f, "invalid argument `{name}`: {reason}")
+            }
+            Self::UnknownCallback(name) => {
+                write!(f, "unknown callback `{name}`: use its full signature")
+            }
+            Self::InvalidCallbackSignature(signature) => write!(
+                f,
+                "callback `{signature}` must have exactly one `bytes` argument"
+            ),
+            Self::InvalidCallbackCall(signature) => write!(
+                f,
+                "`{signature}` must end with an empty `bytes` callback data argument"
+            ),
+            Self::Decode(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(err: DecodeError) -> Self {
+        Self::Decode(err)
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_primitives::{hex, Address};
+
+use crate::decoder::{decode_exec, DecodeError, DecodedCall};
+use crate::encoder::CallMetadata;
+use crate::signatures::find_function;
+use crate::Placeholder;
+
+/// Byte arguments longer than this are elided (callback payloads are shown as nested calls).
+const MAX_BYTES_DISPLAY: usize = 64;
+
+/// Resolves `call_data` against the crate's known functions, returning the function name,
+/// its parameter types and the decoded arguments.
+fn resolve(call_data: &[u8]) -> Option<(&'static str, Vec<DynSolType>, Vec<DynSolValue>)> {
+    let known = find_function(call_data.get(..4)?)?;
+    let (name, params) = known.signature.split_once('(')?;
+
+    let ty = DynSolType::parse(&format!("({params}")).ok()?;
+    let types = ty.as_tuple()?.to_vec();
+    let args = match ty.abi_decode_params(&call_data[4..]).ok()? {
+        DynSolValue::Tuple(args) => args,
+        _ => return None,
+    };
+    Some((name, types, args))
+}
+
+/// Pretty-prints decoded executor call trees as `target.function(args)` lines.
+///
+/// # Example
+/// ```ignore
+/// let explanation = Explainer::new()
+///     .label(dai, "DAI")
+///     .label(executor, "executor")
+///     .explain_exec(&tx.data)?;
+/// println!("{explanation}");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Explainer {
+    labels: HashMap<Address, String>,
+}
+
+impl Explainer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Displays `address` as `name` wherever it appears (targets and arguments).
+    pub fn label(mut self, address: Address, name: impl Into<String>) -> Self {
+        self.labels.insert(address, name.into());
+        self
+    }
+
+    /// Decodes and explains a full `exec_606BaXt(bytes[])` calldata.
+    pub fn explain_exec(&self, data: &[u8]) -> Result<String, DecodeError> {
+        Ok(self.explain(&decode_exec(data)?))
+    }
+
+    /// Explains a list of decoded calls, one line per call, callbacks indented.
+    pub fn explain(&self, calls: &[DecodedCall]) -> String {
+        let mut out = String::new();
+        for call in calls {
+            self.write_call(&mut out, call, None, 0);
+        }
+        out
+    }
+
+    /// Like [`explain`](Self::explain), appending each call's label and tags as
+    /// returned by [`ExecutorEncoder::encode_exec_with_metadata`].
+    ///
+    /// [`ExecutorEncoder::encode_exec_with_metadata`]: crate::ExecutorEncoder::encode_exec_with_metadata
+    pub fn explain_with_metadata(
+        &self,
+        calls: &[DecodedCall],
+        metadata: &[CallMetadata],
+    ) -> String {
+        let mut out = String::new();
+        for (i, call) in calls.iter().enumerate() {
+            self.write_call(&mut out, call, metadata.get(i), 0);
+        }
+        out
+    }
+
+    fn write_call(
+        &self,
+        out: &mut String,
+        call: &DecodedCall,
+        metadata: Option<&CallMetadata>,
+        depth: usize,
+    ) {
+        let indent = "  ".repeat(depth);
+        let mut annotations = Vec::new();
+
+        let _ = write!(out, "{indent}{}.", self.address(call.target));
+        match resolve(&call.call_data) {
+            Some((name, types, args)) => {
+                // Byte range of each top-level argument head, to match placeholders.
+                let mut heads = Vec::with_capacity(types.len());
+                let mut start = 4u64;
+                for ty in &types {
+                    let words = if ty.is_dynamic() {
+                        1
+                    } else {
+                        ty.minimum_words()
+                    };
+                    let end = start + 32 * words as u64;
+                    heads.push(start..end);
+                    start = end;
+                }
+
+                let mut rendered: Vec<String> = args.iter().map(|arg| self.value(arg)).collect();
+                for placeholder in &call.placeholders {
+                    let end = placeholder.offset.saturating_add(placeholder.length);
+                    let patched = heads
+                        .iter()
+                        .position(|head| head.start <= placeholder.offset && end <= head.end);
+                    // Only single-word static arguments are replaced inline.
+                    match patched {
+                        Some(i) if !types[i].is_dynamic() && types[i].minimum_words() == 1 => {
+                            rendered[i] = format!("<{}>", self.placeholder(placeholder));
+                        }
+                        _ => annotations.push(placeholder),
+                    }
+                }
+
+                let _ = write!(out, "{name}({})", rendered.join(", "));
+            }
+            None => {
+                annotations.extend(&call.placeholders);
+                let _ = write!(out, "{}", self.raw_call(&call.call_data));
+            }
+        }
+        if !call.value.is_zero() {
+            let _ = write!(out, " {{value: {}}}", call.value);
+        }
+        if let Some(metadata) = metadata {
+            write_metadata(out, metadata);
+        }
+        out.push('\n');
+
+        for placeholder in annotations {
+            let _ = writeln!(
+                out,
+                "{indent}  calldata[{}..{}] <- {}",
+                placeholder.offset,
+                placeholder.offset.saturating_add(placeholder.length),
+                self.placeholder(placeholder)
+            );
+        }
+
+        if let Some(callback) = &call.callback {
+            let _ = write!(
+                out,
+                "{indent}  callback from {} (data index {})",
+                self.address(call.context.sender),
+                call.context.data_index
+            );
+            if !callback.return_value.is_empty() {
+                let _ = write!(out, " returns 0x{}", hex::encode(&callback.return_value));
+            }
+            out.push_str(":\n");
+            let nested = metadata.map_or(&[][..], |metadata| &metadata.callback[..]);
+            for (i, inner) in callback.calls.iter().enumerate() {
+                self.write_call(out, inner, nested.get(i), depth + 2);
+            }
+        }
+    }
+
+    /// Renders the staticcall a placeholder reads from.
+    fn placeholder(&self, placeholder: &Placeholder) -> String {
+        let source = match resolve(&placeholder.data) {
+            Some((name, _, args)) => {
+                let args: Vec<String> = args.iter().map(|arg| self.value(arg)).collect();
+                format!("{name}({})", args.join(", "))
+            }
+            None => self.raw_call(&placeholder.data),
+        };
+        let mut rendered = format!("{}.{source}", self.address(placeholder.to));
+        if placeholder.resOffset != 0 || placeholder.length != 32 {
+            let _ = write!(
+                rendered,
+                "[{}..{}]",
+                placeholder.resOffset,
+                placeholder.resOffset.saturating_add(placeholder.length)
+            );
+        }
+        rendered
+    }
+
+    fn raw_call(&self, call_data: &[u8]) -> String {
+        match call_data.get(..4) {
+            Some(selector) => format!(
+                "0x{}({})",
+                hex::encode(selector),
+                self.bytes(&call_data[4..])
+            ),
+            None => format!("fallback({})", self.bytes(call_data)),
+        }
+    }
+
+    fn address(&self, address: Address) -> String {
+        self.labels
+            .get(&address)
+            .cloned()
+            .unwrap_or_else(|| address.to_string())
+    }
+
+    fn bytes(&self, bytes: &[u8]) -> String {
+        if bytes.len() > MAX_BYTES_DISPLAY {
+            format!("<{} bytes>", bytes.len())
+        } else {
+            format!("0x{}", hex::encode(bytes))
+        }
+    }
+
+    fn value(&self, value: &DynSolValue) -> String {
+        match value {
+            DynSolValue::Bool(b) => b.to_string(),
+            DynSolValue::Int(i, _) => i.to_string(),
+            DynSolValue::Uint(u, _) => u.to_string(),
+            DynSolValue::FixedBytes(word, size) => format!("0x{}", hex::encode(&word[..*size])),
+            DynSolValue::Address(address) => self.address(*address),
+            DynSolValue::Function(function) => function.to_string(),
+            DynSolValue::Bytes(bytes) => self.bytes(bytes),
+            DynSolValue::String(s) => format!("{s:?}"),
+            DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+                let values: Vec<String> = values.iter().map(|v| self.value(v)).collect();
+                format!("[{}]", values.join(", "))
+            }
+            DynSolValue::Tuple(values) => {
+                let values: Vec<String> = values.iter().map(|v| self.value(v)).collect();
+                format!("({})", values.join(", "))
+            }
+        }
+    }
+}
+
+/// Appends ` # label (key=value, ...)`, if the call has a label or tags.
+fn write_metadata(out: &mut String, metadata: &CallMetadata) {
+    if metadata.label.is_none() && metadata.tags.is_empty() {
+        return;
+    }
+    out.push_str(" #");
+    if let Some(label) = &metadata.label {
+        let _ = write!(out, " {label}");
+    }
+    if !metadata.tags.is_empty() {
+        let tags: Vec<String> = metadata
+            .tags
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        let _ = write!(out, " ({})", tags.join(", "));
+    }
+}
+
+/// Decodes and explains a full `exec_606BaXt(bytes[])` calldata without labels.
+pub fn explain_exec(data: &[u8]) -> Result<String, DecodeError> {
+    Explainer::new().explain_exec(data)
+}
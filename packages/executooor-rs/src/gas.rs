@@ -0,0 +1,125 @@
+//! Offline intrinsic gas estimation of executor transactions.
+//!
+//! Calldata is priced per EIP-2028 (4 gas per zero byte, 16 per non-zero byte) and
+//! floored per EIP-7623 (10 gas per token, 1 token per zero byte and 4 per non-zero
+//! byte), which Prague-activated chains charge when execution is cheap relative to
+//! the calldata size.
+
+use alloy_primitives::Address;
+use alloy_sol_types::SolCall;
+
+use crate::abi;
+use crate::context::encode_context;
+use crate::decoder::{decode_exec, DecodeError, DecodedCall};
+use crate::encoder::EncodedExec;
+use crate::{callWithPlaceholders4845164670Call, call_g0oyU7oCall};
+
+/// Base cost of any transaction.
+pub const TX_BASE_GAS: u64 = 21_000;
+/// EIP-2028 cost of a zero calldata byte.
+pub const ZERO_BYTE_GAS: u64 = 4;
+/// EIP-2028 cost of a non-zero calldata byte.
+pub const NON_ZERO_BYTE_GAS: u64 = 16;
+/// EIP-7623 floor cost per calldata token.
+pub const FLOOR_GAS_PER_TOKEN: u64 = 10;
+
+/// Zero and non-zero byte counts of some calldata.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalldataGas {
+    pub zero_bytes: u64,
+    pub non_zero_bytes: u64,
+}
+
+impl CalldataGas {
+    /// Counts the zero and non-zero bytes of `data`.
+    pub fn of(data: &[u8]) -> Self {
+        let zero_bytes = data.iter().filter(|&&byte| byte == 0).count() as u64;
+        Self {
+            zero_bytes,
+            non_zero_bytes: data.len() as u64 - zero_bytes,
+        }
+    }
+
+    /// Returns the EIP-7623 token count: 1 per zero byte, 4 per non-zero byte.
+    pub fn tokens(&self) -> u64 {
+        self.zero_bytes + 4 * self.non_zero_bytes
+    }
+
+    /// Returns the EIP-2028 calldata cost.
+    pub fn cost(&self) -> u64 {
+        ZERO_BYTE_GAS * self.zero_bytes + NON_ZERO_BYTE_GAS * self.non_zero_bytes
+    }
+
+    /// Returns the EIP-7623 calldata floor cost, excluding the transaction base cost.
+    pub fn floor_cost(&self) -> u64 {
+        FLOOR_GAS_PER_TOKEN * self.tokens()
+    }
+}
+
+/// Calldata cost of a single executor call, as encoded in its parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallGas {
+    pub target: Address,
+    /// Bytes of the whole encoded executor call, including its callback calls.
+    pub calldata: CalldataGas,
+    /// Breakdown of the calls executed in this call's callback.
+    pub callback: Vec<CallGas>,
+}
+
+/// Intrinsic gas of an [`EncodedExec`], broken down per call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GasEstimate {
+    pub calldata: CalldataGas,
+    /// Base cost plus the EIP-2028 calldata cost.
+    pub intrinsic_gas: u64,
+    /// Base cost plus the EIP-7623 calldata floor cost.
+    pub floor_gas: u64,
+    pub calls: Vec<CallGas>,
+}
+
+impl GasEstimate {
+    /// Returns the gas charged for the transaction if its execution costs
+    /// `execution_gas`: the intrinsic cost plus execution, or the floor if higher.
+    pub fn gas_used(&self, execution_gas: u64) -> u64 {
+        (self.intrinsic_gas + execution_gas).max(self.floor_gas)
+    }
+}
+
+/// Estimates the intrinsic gas of `exec`, decoding its calls for the breakdown.
+pub fn estimate_gas(exec: &EncodedExec) -> Result<GasEstimate, DecodeError> {
+    let calldata = CalldataGas::of(&exec.data);
+    let calls = decode_exec(&exec.data)?.iter().map(call_gas).collect();
+    Ok(GasEstimate {
+        calldata,
+        intrinsic_gas: TX_BASE_GAS + calldata.cost(),
+        floor_gas: TX_BASE_GAS + calldata.floor_cost(),
+        calls,
+    })
+}
+
+fn call_gas(call: &DecodedCall) -> CallGas {
+    // Executor calls encode deterministically: re-encoding gives back the original bytes.
+    let selector = if call.placeholders.is_empty() {
+        call_g0oyU7oCall::SELECTOR
+    } else {
+        callWithPlaceholders4845164670Call::SELECTOR
+    };
+    let encoded = abi::encode_call(
+        selector,
+        call.target,
+        call.value,
+        encode_context(call.context.sender, call.context.data_index),
+        &call.call_data,
+        &call.placeholders,
+    );
+    CallGas {
+        target: call.target,
+        calldata: CalldataGas::of(&encoded),
+        callback: call.callback.as_ref().map_or_else(Vec::new, |callback| {
+            callback.calls.iter().map(call_gas).collect()
+        }),
+    }
+}
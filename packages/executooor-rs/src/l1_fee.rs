@@ -0,0 +1,147 @@
+//! Offline L1 data fee estimation of executor transactions on rollups.
+//!
+//! Estimates only cover the `exec_606BaXt` calldata, not the rest of the signed
+//! transaction envelope (nonce, gas limit, recipient, ...). The OP Stack estimators
+//! follow the `GasPriceOracle` and pad the calldata with a 68-byte signature, like
+//! `getL1Fee` does for unsigned transactions.
+
+use alloy_primitives::U256;
+
+use crate::encoder::EncodedExec;
+use crate::gas::CalldataGas;
+
+/// Size of the signature the OP Stack `GasPriceOracle` adds to unsigned transactions.
+pub const OP_SIGNATURE_SIZE: u64 = 68;
+/// Fjord minimum estimated transaction size, in bytes.
+pub const FJORD_MIN_TRANSACTION_SIZE: u64 = 100;
+/// Fjord linear regression intercept, scaled by 1e6.
+pub const FJORD_COST_INTERCEPT: i64 = -42_585_600;
+/// Fjord linear regression FastLZ coefficient, scaled by 1e6.
+pub const FJORD_COST_FASTLZ_COEF: u64 = 836_500;
+/// Arbitrum L1 pricing units charged per compressed byte.
+pub const ARBITRUM_UNITS_PER_BYTE: u64 = 16;
+
+/// L1 fee parameters of an OP Stack chain, as exposed by its `GasPriceOracle`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct OpFeeParams {
+    pub l1_base_fee: U256,
+    pub blob_base_fee: U256,
+    pub base_fee_scalar: u32,
+    pub blob_base_fee_scalar: u32,
+}
+
+impl OpFeeParams {
+    /// Returns `16 * l1BaseFee * baseFeeScalar + blobBaseFee * blobBaseFeeScalar`.
+    fn scaled_fee(&self) -> U256 {
+        U256::from(16) * self.l1_base_fee * U256::from(self.base_fee_scalar)
+            + self.blob_base_fee * U256::from(self.blob_base_fee_scalar)
+    }
+}
+
+/// Estimates the L1 data fee of `exec` on an OP Stack chain since Ecotone, before Fjord.
+pub fn op_ecotone_l1_fee(exec: &EncodedExec, params: &OpFeeParams) -> U256 {
+    let l1_gas_used = CalldataGas::of(&exec.data).cost() + OP_SIGNATURE_SIZE * 16;
+    U256::from(l1_gas_used) * params.scaled_fee() / U256::from(16_000_000u64)
+}
+
+/// Estimates the L1 data fee of `exec` on an OP Stack chain since Fjord.
+pub fn op_fjord_l1_fee(exec: &EncodedExec, params: &OpFeeParams) -> U256 {
+    let fastlz_size = fastlz_compressed_len(&exec.data) as u64 + OP_SIGNATURE_SIZE;
+    U256::from(fjord_estimated_size(fastlz_size)) * params.scaled_fee()
+        / U256::from(1_000_000_000_000u64)
+}
+
+/// Returns Fjord's estimated compressed size of a transaction, scaled by 1e6.
+pub fn fjord_estimated_size(fastlz_size: u64) -> u64 {
+    let estimated =
+        (FJORD_COST_FASTLZ_COEF * fastlz_size).saturating_add_signed(FJORD_COST_INTERCEPT);
+    estimated.max(FJORD_MIN_TRANSACTION_SIZE * 1_000_000)
+}
+
+/// Returns the L1 data fee of a transaction on Arbitrum, given its brotli-compressed
+/// size and the L1 price per unit reported by `ArbGasInfo.getL1BaseFeeEstimate`.
+pub fn arbitrum_l1_fee_for_size(compressed_size: u64, l1_price_per_unit: U256) -> U256 {
+    U256::from(ARBITRUM_UNITS_PER_BYTE * compressed_size) * l1_price_per_unit
+}
+
+/// Estimates the L1 data fee of `exec` on Arbitrum.
+///
+/// ArbOS prices the brotli-compressed transaction. Its size is estimated with FastLZ,
+/// which compresses worse than brotli, so the estimate errs on the expensive side.
+/// Use [`arbitrum_l1_fee_for_size`] with the actual brotli size when available.
+pub fn arbitrum_l1_fee(exec: &EncodedExec, l1_price_per_unit: U256) -> U256 {
+    arbitrum_l1_fee_for_size(fastlz_compressed_len(&exec.data) as u64, l1_price_per_unit)
+}
+
+/// Returns the length of the FastLZ (level 1) compression of `data`, as computed by the
+/// OP Stack to price transactions since Fjord (`LibZip.flzCompress`).
+pub fn fastlz_compressed_len(data: &[u8]) -> usize {
+    const HASH_SIZE: usize = 1 << 13;
+    const MAX_DISTANCE: usize = HASH_SIZE - 1;
+
+    let u24 = |i: usize| {
+        u32::from(data[i]) | (u32::from(data[i + 1]) << 8) | (u32::from(data[i + 2]) << 16)
+    };
+    let hash = |value: u32| (value.wrapping_mul(2_654_435_769) >> 19) as usize & (HASH_SIZE - 1);
+    // Literal runs are encoded in chunks of up to 32 bytes, each with a 1-byte header.
+    let literals = |len: usize| {
+        33 * (len / 32)
+            + if len.is_multiple_of(32) {
+                0
+            } else {
+                len % 32 + 1
+            }
+    };
+    // Matches take 2 or 3 bytes, plus 3 bytes per 262 matched bytes.
+    let matched = |len: usize| {
+        let len = len - 1;
+        3 * (len / 262) + if len % 262 >= 6 { 3 } else { 2 }
+    };
+
+    let mut table = vec![0usize; HASH_SIZE];
+    let mut size = 0;
+    let mut anchor = 0;
+    let limit = data.len().saturating_sub(13);
+    let mut ip = 2;
+    while ip < limit {
+        let mut reference;
+        loop {
+            let sequence = u24(ip);
+            let h = hash(sequence);
+            reference = table[h];
+            table[h] = ip;
+            if ip >= limit {
+                break;
+            }
+            ip += 1;
+            if ip - 1 - reference <= MAX_DISTANCE && sequence == u24(reference) {
+                break;
+            }
+        }
+        if ip >= limit {
+            break;
+        }
+
+        ip -= 1;
+        if ip > anchor {
+            size += literals(ip - anchor);
+        }
+        let (p, q) = (reference + 3, ip + 3);
+        let len = (0..limit + 9 - q)
+            .take_while(|&l| data[p + l] == data[q + l])
+            .count();
+        // The first mismatching byte is counted in the match, as in the reference.
+        let len = (len + 1).min(limit + 9 - q);
+        size += matched(len);
+
+        ip += len;
+        table[hash(u24(ip))] = ip;
+        ip += 1;
+        table[hash(u24(ip))] = ip;
+        ip += 1;
+        anchor = ip;
+    }
+    size + literals(data.len() - anchor)
+}
@@ -0,0 +1,114 @@
+use alloy_dyn_abi::{DynSolType, Specifier};
+use alloy_json_abi::{Function, Param};
+use alloy_sol_types::{JsonAbiExt, SolCall};
+
+use crate::decoder::read_word;
+use crate::error::Error;
+
+/// Computes the byte offset in `call_data` of the static argument at `path`.
+///
+/// `call_data` is the selector-prefixed encoding of a call to `function`. `path` is a
+/// `.`-separated list of argument names or indices, descending into tuples/structs and
+/// arrays, e.g. `"amount"`, `"params.amountIn"` or `"0.3"`.
+///
+/// The result is the `offset` a [`Placeholder`](crate::Placeholder) needs to overwrite
+/// that argument. Offsets of arguments nested in dynamic types depend on the encoded
+/// data, which is why the call data is required.
+pub fn arg_offset(function: &Function, call_data: &[u8], path: &str) -> Result<u64, Error> {
+    let invalid = || Error::InvalidArgumentPath(path.to_string());
+
+    let mut ty = DynSolType::Tuple(resolve_params(&function.inputs).ok_or_else(invalid)?);
+    let mut params: &[Param] = &function.inputs;
+    // Absolute position of the encoding of the current value.
+    let mut start = 4usize;
+
+    for segment in path.split('.') {
+        let (element, head, names) = match &ty {
+            DynSolType::Tuple(components) => {
+                let index = match segment.parse::<usize>() {
+                    Ok(index) => index,
+                    Err(_) => params
+                        .iter()
+                        .position(|param| param.name == segment)
+                        .ok_or_else(invalid)?,
+                };
+                let element = components.get(index).ok_or_else(invalid)?;
+                let head = start + 32 * components[..index].iter().map(head_words).sum::<usize>();
+                let names = params.get(index).map_or(&[][..], |p| &p.components[..]);
+                (element.clone(), head, names)
+            }
+            DynSolType::FixedArray(element, len) => {
+                let index: usize = segment.parse().map_err(|_| invalid())?;
+                if index >= *len {
+                    return Err(invalid());
+                }
+                (
+                    (**element).clone(),
+                    start + 32 * index * head_words(element),
+                    params,
+                )
+            }
+            DynSolType::Array(element) => {
+                let index: usize = segment.parse().map_err(|_| invalid())?;
+                if index >= read_word(call_data, start).ok_or_else(invalid)? {
+                    return Err(invalid());
+                }
+                // Elements are encoded after the length word.
+                start += 32;
+                let head = index
+                    .checked_mul(32 * head_words(element))
+                    .and_then(|offset| start.checked_add(offset))
+                    .ok_or_else(invalid)?;
+                ((**element).clone(), head, params)
+            }
+            _ => return Err(invalid()),
+        };
+
+        start = if element.is_dynamic() {
+            start
+                .checked_add(read_word(call_data, head).ok_or_else(invalid)?)
+                .ok_or_else(invalid)?
+        } else {
+            head
+        };
+        ty = element;
+        params = names;
+    }
+
+    if ty.is_dynamic() {
+        return Err(Error::DynamicArgument(path.to_string()));
+    }
+    Ok(start as u64)
+}
+
+/// Computes the byte offset of the static argument at `path` in the encoding of `call`.
+///
+/// Requires the call to be declared with `#[sol(abi)]` so argument names are available.
+///
+/// # Example
+/// ```ignore
+/// sol! {
+///     #[sol(abi)]
+///     function exactInput(ExactInputParams params) external payable returns (uint256);
+/// }
+/// let offset = sol_arg_offset(&call, "params.amountIn")?; // 4 + 32 * 4
+/// ```
+pub fn sol_arg_offset<C>(call: &C, path: &str) -> Result<u64, Error>
+where
+    C: SolCall + JsonAbiExt<Abi = Function>,
+{
+    arg_offset(&C::abi(), &call.abi_encode(), path)
+}
+
+fn resolve_params(params: &[Param]) -> Option<Vec<DynSolType>> {
+    params.iter().map(|param| param.resolve().ok()).collect()
+}
+
+/// Number of head words taken by a value of type `ty` in its enclosing tuple.
+fn head_words(ty: &DynSolType) -> usize {
+    if ty.is_dynamic() {
+        1
+    } else {
+        ty.minimum_words()
+    }
+}
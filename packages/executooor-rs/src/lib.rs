@@ -1,17 +1,53 @@
 use alloy_sol_types::sol;
 
+pub(crate) mod abi;
+pub mod action;
+pub mod callbacks;
 pub mod context;
+pub mod decoder;
+pub mod deploy;
+pub mod dynamic;
 pub mod encoder;
+pub mod error;
+pub mod explain;
+pub mod gas;
+pub mod l1_fee;
+pub mod layout;
 pub mod math;
 pub mod protocols;
+pub(crate) mod signatures;
+#[cfg(feature = "strategy")]
+pub mod strategy;
 pub mod types;
 
-pub use context::encode_context;
-pub use encoder::{EncodedExec, ExecutorEncoder};
+pub use action::{encode_actions, Action};
+pub use callbacks::{callback_data_index, CallbackSignature};
+pub use context::{decode_context, encode_context};
+pub use decoder::{decode_exec, DecodedCall, DecodedCallback, ExecPlan};
+pub use deploy::deploy_bytecode;
+pub use dynamic::{encode_dyn_call, find_function, parse_abi};
+pub use encoder::{CallMetadata, Checkpoint, EncodedExec, ExecutorEncoder};
+pub use error::Error;
+pub use explain::Explainer;
+pub use gas::{estimate_gas, CallGas, CalldataGas, GasEstimate};
+pub use l1_fee::{arbitrum_l1_fee, op_ecotone_l1_fee, op_fjord_l1_fee, OpFeeParams};
+pub use layout::{arg_offset, sol_arg_offset};
 pub use types::{AssetRequest, CallbackContext, MarketParams};
 
+#[cfg(feature = "macros")]
+pub use executooor_macros::sol_encoder;
+
+/// Dependencies of the code generated by [`sol_encoder!`].
+#[doc(hidden)]
+pub mod __private {
+    pub use alloy_primitives;
+    pub use alloy_sol_types;
+}
+
 sol! {
     /// Placeholder struct for dynamic data injection via staticcalls.
+    #[derive(Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     struct Placeholder {
         address to;
         bytes data;
@@ -42,5 +78,8 @@ pub(crate) mod executor_sol {
 
     sol! {
         function transfer(address recipient, uint256 amount);
+
+        #[sol(abi)]
+        function call_g0oyU7o(address target, uint256 value, bytes32 context, bytes callData);
     }
 }
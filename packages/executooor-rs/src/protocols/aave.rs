@@ -1,9 +1,12 @@
 use alloy_primitives::{Address, U256};
 use alloy_sol_types::SolCall;
 
+use crate::action::Action;
 use crate::encoder::ExecutorEncoder;
+use crate::error::Error;
+use crate::Placeholder;
 
-mod aave_sol {
+pub(crate) mod aave_sol {
     use alloy_sol_types::sol;
 
     sol! {
@@ -35,6 +38,15 @@ mod aave_sol {
             address to
         ) external;
 
+        function getUserAccountData(address user) external view returns (
+            uint256 totalCollateralBase,
+            uint256 totalDebtBase,
+            uint256 availableBorrowsBase,
+            uint256 currentLiquidationThreshold,
+            uint256 ltv,
+            uint256 healthFactor
+        );
+
         function liquidationCall(
             address collateralAsset,
             address debtAsset,
@@ -45,7 +57,34 @@ mod aave_sol {
     }
 }
 
+/// A field of the Aave pool `getUserAccountData` return tuple.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AaveAccountData {
+    TotalCollateralBase = 0,
+    TotalDebtBase = 1,
+    AvailableBorrowsBase = 2,
+    CurrentLiquidationThreshold = 3,
+    Ltv = 4,
+    HealthFactor = 5,
+}
+
 impl ExecutorEncoder {
+    /// Creates a `Placeholder` that reads `field` of `getUserAccountData(user)` from the pool.
+    pub fn aave_user_account_data(
+        &self,
+        pool: Address,
+        user: Address,
+        field: AaveAccountData,
+        offset: u64,
+    ) -> Placeholder {
+        self.view_placeholder(
+            pool,
+            aave_sol::getUserAccountDataCall { user },
+            field as u64,
+            offset,
+        )
+    }
+
     /// Supplies `amount` of `asset` to the Aave pool.
     pub fn aave_supply(
         &mut self,
@@ -145,3 +184,153 @@ impl ExecutorEncoder {
         self.push_call(pool, U256::ZERO, call_data, None, vec![])
     }
 }
+
+/// Supplies `amount` of `asset`, see [`ExecutorEncoder::aave_supply`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct AaveSupply {
+    pub pool: Address,
+    pub asset: Address,
+    pub amount: U256,
+    pub on_behalf_of: Option<Address>,
+}
+
+impl Action for AaveSupply {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.aave_supply(self.pool, self.asset, self.amount, self.on_behalf_of);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+}
+
+/// Borrows `amount` of `asset`, see [`ExecutorEncoder::aave_borrow`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct AaveBorrow {
+    pub pool: Address,
+    pub asset: Address,
+    pub amount: U256,
+    pub interest_rate_mode: U256,
+    pub on_behalf_of: Option<Address>,
+}
+
+impl Action for AaveBorrow {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.aave_borrow(
+            self.pool,
+            self.asset,
+            self.amount,
+            self.interest_rate_mode,
+            self.on_behalf_of,
+        );
+        Ok(())
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+}
+
+/// Repays `amount` of `asset`, see [`ExecutorEncoder::aave_repay`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct AaveRepay {
+    pub pool: Address,
+    pub asset: Address,
+    pub amount: U256,
+    pub interest_rate_mode: U256,
+    pub on_behalf_of: Option<Address>,
+}
+
+impl Action for AaveRepay {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.aave_repay(
+            self.pool,
+            self.asset,
+            self.amount,
+            self.interest_rate_mode,
+            self.on_behalf_of,
+        );
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+}
+
+/// Withdraws `amount` of `asset`, see [`ExecutorEncoder::aave_withdraw`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct AaveWithdraw {
+    pub pool: Address,
+    pub asset: Address,
+    pub amount: U256,
+    pub to: Option<Address>,
+}
+
+impl Action for AaveWithdraw {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.aave_withdraw(self.pool, self.asset, self.amount, self.to);
+        Ok(())
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+}
+
+/// Liquidates `user`, see [`ExecutorEncoder::aave_liquidate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct AaveLiquidate {
+    pub pool: Address,
+    pub collateral: Address,
+    pub debt: Address,
+    pub user: Address,
+    pub amount: U256,
+}
+
+impl Action for AaveLiquidate {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.aave_liquidate(
+            self.pool,
+            self.collateral,
+            self.debt,
+            self.user,
+            self.amount,
+        );
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.debt]
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.collateral]
+    }
+}
@@ -3,7 +3,7 @@ use alloy_sol_types::SolCall;
 
 use crate::encoder::ExecutorEncoder;
 
-mod compound_sol {
+pub(crate) mod compound_sol {
     use alloy_sol_types::sol;
 
     sol! {
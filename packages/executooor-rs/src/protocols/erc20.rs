@@ -1,7 +1,9 @@
 use alloy_primitives::{Address, Bytes, U256};
 use alloy_sol_types::SolCall;
 
+use crate::action::Action;
 use crate::encoder::ExecutorEncoder;
+use crate::error::Error;
 use crate::Placeholder;
 
 pub(crate) mod erc20_sol {
@@ -9,12 +11,20 @@ pub(crate) mod erc20_sol {
 
     sol! {
         function balanceOf(address owner) external view returns (uint256);
+        function allowance(address owner, address spender) external view returns (uint256);
+        #[sol(abi)]
         function approve(address spender, uint256 amount) external returns (bool);
+        #[sol(abi)]
         function transfer(address to, uint256 amount) external returns (bool);
         function transferFrom(address from, address to, uint256 amount) external returns (bool);
     }
 }
 
+/// Offset of the `amount` argument in the `approve` call data.
+const APPROVE_AMOUNT_OFFSET: u64 = 4 + 32;
+/// Offset of the `amount` argument in the `transfer` call data.
+const TRANSFER_AMOUNT_OFFSET: u64 = 4 + 32;
+
 impl ExecutorEncoder {
     /// Builds an ERC20 approve call wrapped in the executor call (static method).
     ///
@@ -44,13 +54,23 @@ impl ExecutorEncoder {
     /// The result (32 bytes at response offset 0) is placed at `offset` in the call data.
     /// This is used by methods like `erc20_approve_all` and `erc20_skim`.
     pub fn erc20_balance_of(&self, asset: Address, owner: Address, offset: u64) -> Placeholder {
-        Placeholder {
-            to: asset,
-            data: erc20_sol::balanceOfCall { owner }.abi_encode().into(),
+        self.view_placeholder(asset, erc20_sol::balanceOfCall { owner }, 0, offset)
+    }
+
+    /// Creates a `Placeholder` that reads `allowance(owner, spender)` from `asset`.
+    pub fn erc20_allowance(
+        &self,
+        asset: Address,
+        owner: Address,
+        spender: Address,
+        offset: u64,
+    ) -> Placeholder {
+        self.view_placeholder(
+            asset,
+            erc20_sol::allowanceCall { owner, spender },
+            0,
             offset,
-            length: 32,
-            resOffset: 0,
-        }
+        )
     }
 
     /// Approves `spender` to spend `allowance` of `asset`.
@@ -73,8 +93,8 @@ impl ExecutorEncoder {
     ///
     /// Uses a placeholder to dynamically read the balance at execution time.
     pub fn erc20_approve_all(&mut self, asset: Address, spender: Address) -> &mut Self {
-        let placeholder = self.erc20_balance_of(asset, self.address(), 4 + 32);
-        let call_data: Bytes = erc20_sol::approveCall {
+        let placeholder = self.erc20_balance_of(asset, self.address(), APPROVE_AMOUNT_OFFSET);
+        let call_data = erc20_sol::approveCall {
             spender,
             amount: U256::ZERO,
         }
@@ -121,8 +141,8 @@ impl ExecutorEncoder {
     ///
     /// Uses a placeholder to dynamically read the balance at execution time.
     pub fn erc20_skim(&mut self, asset: Address, recipient: Address) -> &mut Self {
-        let placeholder = self.erc20_balance_of(asset, self.address(), 4 + 32);
-        let call_data: Bytes = erc20_sol::transferCall {
+        let placeholder = self.erc20_balance_of(asset, self.address(), TRANSFER_AMOUNT_OFFSET);
+        let call_data = erc20_sol::transferCall {
             to: recipient,
             amount: U256::ZERO,
         }
@@ -131,3 +151,144 @@ impl ExecutorEncoder {
         self.push_call(asset, U256::ZERO, call_data, None, vec![placeholder])
     }
 }
+
+/// Approves `allowance`, see [`ExecutorEncoder::erc20_approve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc20Approve {
+    pub asset: Address,
+    pub spender: Address,
+    pub allowance: U256,
+}
+
+impl Action for Erc20Approve {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc20_approve(self.asset, self.spender, self.allowance);
+        Ok(())
+    }
+}
+
+/// Approves the whole balance of `asset`, see [`ExecutorEncoder::erc20_approve_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc20ApproveAll {
+    pub asset: Address,
+    pub spender: Address,
+}
+
+impl Action for Erc20ApproveAll {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc20_approve_all(self.asset, self.spender);
+        Ok(())
+    }
+}
+
+/// Transfers `amount`, see [`ExecutorEncoder::erc20_transfer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc20Transfer {
+    pub asset: Address,
+    pub recipient: Address,
+    pub amount: U256,
+}
+
+impl Action for Erc20Transfer {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc20_transfer(self.asset, self.recipient, self.amount);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+}
+
+/// Transfers `amount` from `owner`, see [`ExecutorEncoder::erc20_transfer_from`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc20TransferFrom {
+    pub asset: Address,
+    pub owner: Address,
+    pub recipient: Address,
+    pub amount: U256,
+}
+
+impl Action for Erc20TransferFrom {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc20_transfer_from(self.asset, self.owner, self.recipient, self.amount);
+        Ok(())
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+}
+
+/// Transfers the whole balance of `asset`, see [`ExecutorEncoder::erc20_skim`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc20Skim {
+    pub asset: Address,
+    pub recipient: Address,
+}
+
+impl Action for Erc20Skim {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc20_skim(self.asset, self.recipient);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::sol_arg_offset;
+
+    #[test]
+    fn test_approve_amount_offset() {
+        let call = erc20_sol::approveCall {
+            spender: Address::ZERO,
+            amount: U256::ZERO,
+        };
+        assert_eq!(
+            sol_arg_offset(&call, "amount").unwrap(),
+            APPROVE_AMOUNT_OFFSET
+        );
+    }
+
+    #[test]
+    fn test_transfer_amount_offset() {
+        let call = erc20_sol::transferCall {
+            to: Address::ZERO,
+            amount: U256::ZERO,
+        };
+        assert_eq!(
+            sol_arg_offset(&call, "amount").unwrap(),
+            TRANSFER_AMOUNT_OFFSET
+        );
+    }
+}
@@ -3,15 +3,22 @@ use alloy_sol_types::SolCall;
 
 use crate::encoder::ExecutorEncoder;
 
-mod erc20_wrapper_sol {
+pub(crate) mod erc20_wrapper_sol {
     use alloy_sol_types::sol;
 
     sol! {
+        #[sol(abi)]
         function depositFor(address owner, uint256 amount) external;
+        #[sol(abi)]
         function withdrawTo(address receiver, uint256 amount) external;
     }
 }
 
+/// Offset of the `amount` argument in the `depositFor` call data.
+const DEPOSIT_FOR_AMOUNT_OFFSET: u64 = 4 + 32;
+/// Offset of the `amount` argument in the `withdrawTo` call data.
+const WITHDRAW_TO_AMOUNT_OFFSET: u64 = 4 + 32;
+
 impl ExecutorEncoder {
     /// Deposits `amount` of the underlying token into the wrapper on behalf of `on_behalf`.
     pub fn erc20_wrapper_deposit_for(
@@ -38,7 +45,8 @@ impl ExecutorEncoder {
         underlying: Address,
         on_behalf: Address,
     ) -> &mut Self {
-        let placeholder = self.erc20_balance_of(underlying, self.address(), 4 + 32);
+        let placeholder =
+            self.erc20_balance_of(underlying, self.address(), DEPOSIT_FOR_AMOUNT_OFFSET);
         let call_data = erc20_wrapper_sol::depositForCall {
             owner: on_behalf,
             amount: U256::ZERO,
@@ -69,7 +77,7 @@ impl ExecutorEncoder {
         asset: Address,
         receiver: Address,
     ) -> &mut Self {
-        let placeholder = self.erc20_balance_of(asset, self.address(), 4 + 32);
+        let placeholder = self.erc20_balance_of(asset, self.address(), WITHDRAW_TO_AMOUNT_OFFSET);
         let call_data = erc20_wrapper_sol::withdrawToCall {
             receiver,
             amount: U256::ZERO,
@@ -79,3 +87,33 @@ impl ExecutorEncoder {
         self.push_call(asset, U256::ZERO, call_data, None, vec![placeholder])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::sol_arg_offset;
+
+    #[test]
+    fn test_deposit_for_amount_offset() {
+        let call = erc20_wrapper_sol::depositForCall {
+            owner: Address::ZERO,
+            amount: U256::ZERO,
+        };
+        assert_eq!(
+            sol_arg_offset(&call, "amount").unwrap(),
+            DEPOSIT_FOR_AMOUNT_OFFSET
+        );
+    }
+
+    #[test]
+    fn test_withdraw_to_amount_offset() {
+        let call = erc20_wrapper_sol::withdrawToCall {
+            receiver: Address::ZERO,
+            amount: U256::ZERO,
+        };
+        assert_eq!(
+            sol_arg_offset(&call, "amount").unwrap(),
+            WITHDRAW_TO_AMOUNT_OFFSET
+        );
+    }
+}
@@ -1,20 +1,42 @@
 use alloy_primitives::{Address, U256};
 use alloy_sol_types::SolCall;
 
+use crate::action::Action;
 use crate::encoder::ExecutorEncoder;
+use crate::error::Error;
+use crate::Placeholder;
 
-mod erc4626_sol {
+pub(crate) mod erc4626_sol {
     use alloy_sol_types::sol;
 
     sol! {
+        #[sol(abi)]
         function deposit(uint256 assets, address receiver) external returns (uint256);
         function mint(uint256 shares, address receiver) external returns (uint256);
         function withdraw(uint256 assets, address receiver, address owner) external returns (uint256);
+        #[sol(abi)]
         function redeem(uint256 shares, address receiver, address owner) external returns (uint256);
+        function previewRedeem(uint256 shares) external view returns (uint256);
+        function maxWithdraw(address owner) external view returns (uint256);
     }
 }
 
+/// Offset of the `assets` argument in the `deposit` call data.
+const DEPOSIT_ASSETS_OFFSET: u64 = 4;
+/// Offset of the `shares` argument in the `redeem` call data.
+const REDEEM_SHARES_OFFSET: u64 = 4;
+
 impl ExecutorEncoder {
+    /// Creates a `Placeholder` that reads `previewRedeem(shares)` from the vault.
+    pub fn erc4626_preview_redeem(&self, vault: Address, shares: U256, offset: u64) -> Placeholder {
+        self.view_placeholder(vault, erc4626_sol::previewRedeemCall { shares }, 0, offset)
+    }
+
+    /// Creates a `Placeholder` that reads `maxWithdraw(owner)` from the vault.
+    pub fn erc4626_max_withdraw(&self, vault: Address, owner: Address, offset: u64) -> Placeholder {
+        self.view_placeholder(vault, erc4626_sol::maxWithdrawCall { owner }, 0, offset)
+    }
+
     /// Deposits `assets` into the ERC4626 vault.
     ///
     /// `owner` is the receiver of the vault shares (named to match the TS API).
@@ -37,7 +59,7 @@ impl ExecutorEncoder {
         asset: Address,
         owner: Address,
     ) -> &mut Self {
-        let placeholder = self.erc20_balance_of(asset, self.address(), 4);
+        let placeholder = self.erc20_balance_of(asset, self.address(), DEPOSIT_ASSETS_OFFSET);
         let call_data = erc4626_sol::depositCall {
             assets: U256::ZERO,
             receiver: owner,
@@ -103,7 +125,7 @@ impl ExecutorEncoder {
         receiver: Address,
         owner: Address,
     ) -> &mut Self {
-        let placeholder = self.erc20_balance_of(vault, self.address(), 4);
+        let placeholder = self.erc20_balance_of(vault, self.address(), REDEEM_SHARES_OFFSET);
         let call_data = erc4626_sol::redeemCall {
             shares: U256::ZERO,
             receiver,
@@ -114,3 +136,213 @@ impl ExecutorEncoder {
         self.push_call(vault, U256::ZERO, call_data, None, vec![placeholder])
     }
 }
+
+/// Deposits `assets`, see [`ExecutorEncoder::erc4626_deposit`].
+///
+/// `asset` is the vault's underlying token, as are `asset` fields of the other ERC4626
+/// actions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc4626Deposit {
+    pub vault: Address,
+    pub asset: Address,
+    pub assets: U256,
+    pub owner: Address,
+}
+
+impl Action for Erc4626Deposit {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc4626_deposit(self.vault, self.assets, self.owner);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.vault]
+    }
+}
+
+/// Deposits the whole balance of `asset`, see [`ExecutorEncoder::erc4626_deposit_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc4626DepositAll {
+    pub vault: Address,
+    pub asset: Address,
+    pub owner: Address,
+}
+
+impl Action for Erc4626DepositAll {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc4626_deposit_all(self.vault, self.asset, self.owner);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.vault]
+    }
+}
+
+/// Mints `shares`, see [`ExecutorEncoder::erc4626_mint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc4626Mint {
+    pub vault: Address,
+    pub asset: Address,
+    pub shares: U256,
+    pub owner: Address,
+}
+
+impl Action for Erc4626Mint {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc4626_mint(self.vault, self.shares, self.owner);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.vault]
+    }
+}
+
+/// Withdraws `assets`, see [`ExecutorEncoder::erc4626_withdraw`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc4626Withdraw {
+    pub vault: Address,
+    pub asset: Address,
+    pub assets: U256,
+    pub receiver: Address,
+    pub owner: Address,
+}
+
+impl Action for Erc4626Withdraw {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc4626_withdraw(self.vault, self.assets, self.receiver, self.owner);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.vault]
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+}
+
+/// Redeems `shares`, see [`ExecutorEncoder::erc4626_redeem`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc4626Redeem {
+    pub vault: Address,
+    pub asset: Address,
+    pub shares: U256,
+    pub receiver: Address,
+    pub owner: Address,
+}
+
+impl Action for Erc4626Redeem {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc4626_redeem(self.vault, self.shares, self.receiver, self.owner);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.vault]
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+}
+
+/// Redeems the executor's whole balance of shares, see
+/// [`ExecutorEncoder::erc4626_redeem_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct Erc4626RedeemAll {
+    pub vault: Address,
+    pub asset: Address,
+    pub receiver: Address,
+    pub owner: Address,
+}
+
+impl Action for Erc4626RedeemAll {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc4626_redeem_all(self.vault, self.receiver, self.owner);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.vault]
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.asset]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::sol_arg_offset;
+
+    #[test]
+    fn test_deposit_assets_offset() {
+        let call = erc4626_sol::depositCall {
+            assets: U256::ZERO,
+            receiver: Address::ZERO,
+        };
+        assert_eq!(
+            sol_arg_offset(&call, "assets").unwrap(),
+            DEPOSIT_ASSETS_OFFSET
+        );
+    }
+
+    #[test]
+    fn test_redeem_shares_offset() {
+        let call = erc4626_sol::redeemCall {
+            shares: U256::ZERO,
+            receiver: Address::ZERO,
+            owner: Address::ZERO,
+        };
+        assert_eq!(
+            sol_arg_offset(&call, "shares").unwrap(),
+            REDEEM_SHARES_OFFSET
+        );
+    }
+}
@@ -1,11 +1,20 @@
+use std::fmt;
+use std::sync::Arc;
+
 use alloy_primitives::{Address, Bytes, U256};
 use alloy_sol_types::SolCall;
 
-use crate::encoder::{encode_callback_data, ExecutorEncoder};
+use crate::action::{encode_with_callback, Action};
+use crate::callbacks::{
+    EXECUTE_OPERATION, ON_FLASH_LOAN, ON_MORPHO_FLASH_LOAN, RECEIVE_FLASH_LOAN, UNISWAP_V2_CALL,
+    UNISWAP_V3_FLASH_CALLBACK,
+};
+use crate::encoder::ExecutorEncoder;
+use crate::error::Error;
 use crate::math::{mul_div_up, percent_mul};
 use crate::types::{AssetRequest, CallbackContext};
 
-mod balancer_sol {
+pub(crate) mod balancer_sol {
     use alloy_sol_types::sol;
 
     sol! {
@@ -18,7 +27,7 @@ mod balancer_sol {
     }
 }
 
-mod maker_sol {
+pub(crate) mod maker_sol {
     use alloy_sol_types::sol;
 
     sol! {
@@ -31,7 +40,7 @@ mod maker_sol {
     }
 }
 
-mod aave_pool_sol {
+pub(crate) mod aave_pool_sol {
     use alloy_sol_types::sol;
 
     sol! {
@@ -47,7 +56,7 @@ mod aave_pool_sol {
     }
 }
 
-mod uni_flash_sol {
+pub(crate) mod uni_flash_sol {
     use alloy_sol_types::sol;
 
     sol! {
@@ -60,7 +69,7 @@ mod uni_flash_sol {
     }
 }
 
-mod morpho_blue_flash_sol {
+pub(crate) mod morpho_blue_flash_sol {
     use alloy_sol_types::sol;
 
     sol! {
@@ -72,10 +81,272 @@ mod morpho_blue_flash_sol {
     }
 }
 
+/// Checks that a flash loan borrows at least one non-zero amount, each asset at most once,
+/// and never a non-zero amount of the zero address.
+fn check_requests(requests: impl IntoIterator<Item = (Address, U256)>) -> Result<(), Error> {
+    let mut borrowed: Vec<Address> = Vec::new();
+    for (index, (asset, amount)) in requests.into_iter().enumerate() {
+        if amount.is_zero() {
+            continue;
+        }
+        if asset == Address::ZERO {
+            return Err(Error::ZeroAsset { index });
+        }
+        if borrowed.contains(&asset) {
+            return Err(Error::DuplicateAsset(asset));
+        }
+        borrowed.push(asset);
+    }
+    if borrowed.is_empty() {
+        return Err(Error::EmptyFlashLoan);
+    }
+    Ok(())
+}
+
+/// Fee a lender charges on top of each borrowed amount.
+#[derive(Clone, Default)]
+pub enum Fee {
+    #[default]
+    None,
+    /// In basis points, rounded half up as Aave's `percentMul` (e.g., 5 = 0.05%).
+    Percent(U256),
+    /// In hundredths of a basis point, rounded up as Uniswap V3 (e.g., 500 = 0.05%).
+    Pips(U256),
+    /// Computed from the borrowed amount, e.g. `Arc::new(move |amount| amount * bps / 10_000)`.
+    Custom(Arc<dyn Fn(U256) -> U256 + Send + Sync>),
+}
+
+impl Fee {
+    /// Returns the fee owed on `amount`.
+    pub fn of(&self, amount: U256) -> U256 {
+        match self {
+            Self::None => U256::ZERO,
+            Self::Percent(percentage) => percent_mul(amount, *percentage),
+            Self::Pips(pips) => mul_div_up(amount, *pips, U256::from(1_000_000u64)),
+            Self::Custom(fee) => fee(amount),
+        }
+    }
+}
+
+impl fmt::Debug for Fee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::Percent(percentage) => f.debug_tuple("Percent").field(percentage).finish(),
+            Self::Pips(pips) => f.debug_tuple("Pips").field(pips).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// How a lender takes back its loans at the end of the callback.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RepaymentMethod {
+    /// The lender takes nothing back, or the callback calls repay it themselves.
+    #[default]
+    None,
+    /// The executor transfers each loan plus fee to this address.
+    Transfer(Address),
+    /// The executor approves this address to pull each loan plus fee.
+    Approve(Address),
+}
+
+/// Calls that repay the loans of a callback, appended after the callback calls.
+#[derive(Debug, Clone, Default)]
+pub struct Repayment {
+    pub method: RepaymentMethod,
+    pub loans: Vec<AssetRequest>,
+    pub fee: Fee,
+}
+
+impl Repayment {
+    /// Repays nothing.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Repays `loans` by transferring them to `to`.
+    pub fn transfer(to: Address, loans: Vec<AssetRequest>) -> Self {
+        Self {
+            method: RepaymentMethod::Transfer(to),
+            loans,
+            fee: Fee::None,
+        }
+    }
+
+    /// Repays `loans` by approving `spender` to pull them.
+    pub fn approve(spender: Address, loans: Vec<AssetRequest>) -> Self {
+        Self {
+            method: RepaymentMethod::Approve(spender),
+            loans,
+            fee: Fee::None,
+        }
+    }
+
+    /// Repays `fee` on top of each loan.
+    pub fn with_fee(mut self, fee: Fee) -> Self {
+        self.fee = fee;
+        self
+    }
+
+    /// Returns the repayment calls, one per loan.
+    pub fn calls(&self) -> Vec<Bytes> {
+        self.loans
+            .iter()
+            .filter_map(|loan| {
+                let amount = loan.amount + self.fee.of(loan.amount);
+                match self.method {
+                    RepaymentMethod::None => None,
+                    RepaymentMethod::Transfer(to) => Some(ExecutorEncoder::build_erc20_transfer(
+                        loan.asset, to, amount,
+                    )),
+                    RepaymentMethod::Approve(spender) => Some(
+                        ExecutorEncoder::build_erc20_approve(loan.asset, spender, amount),
+                    ),
+                }
+            })
+            .collect()
+    }
+}
+
+fn loans(assets: [Address; 2], amounts: [U256; 2]) -> Vec<AssetRequest> {
+    assets
+        .into_iter()
+        .zip(amounts)
+        .map(|(asset, amount)| AssetRequest { asset, amount })
+        .collect()
+}
+
 impl ExecutorEncoder {
+    /// Pushes `call` to `target`, which calls the executor back as per `context`: the
+    /// callback runs `callback_calls` then the `repayment` calls, and returns `return_value`.
+    ///
+    /// `call` must be encoded with an empty `bytes` in place of the callback data, as in
+    /// [`push_callback_call`](Self::push_callback_call). This is the building block of
+    /// every flash loan method.
+    ///
+    /// Unless `callback_calls` is `None` or empty, the value and metadata of the calls
+    /// drained by [`flush`](Self::flush) or [`nest_callback`](Self::nest_callback) since
+    /// the last callback call are attached to this call, as in
+    /// [`push_callback_call`](Self::push_callback_call).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `call` doesn't end with an empty `bytes`: see
+    /// [`try_callback_call`](Self::try_callback_call).
+    ///
+    /// # Example
+    /// ```ignore
+    /// // An ERC3156 lender charging 0.09%.
+    /// let call = flashLoanCall { receiver: encoder.address(), token, amount, data: Bytes::new() };
+    /// let repayment = Repayment::approve(lender, vec![AssetRequest { asset: token, amount }])
+    ///     .with_fee(Fee::Percent(U256::from(9)));
+    /// encoder.callback_call(lender, call, &ON_FLASH_LOAN.context(lender), magic, &repayment, calls);
+    /// ```
+    pub fn callback_call<C: SolCall>(
+        &mut self,
+        target: Address,
+        call: C,
+        context: &CallbackContext,
+        return_value: Bytes,
+        repayment: &Repayment,
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> &mut Self {
+        self.try_callback_call(
+            target,
+            call,
+            context,
+            return_value,
+            repayment,
+            callback_calls,
+        )
+        .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`callback_call`](Self::callback_call).
+    ///
+    /// Returns [`Error::InvalidCallbackCall`] unless the last dynamic argument of `C` is a
+    /// `bytes`, and `call` encodes it as empty at the end of its call data.
+    pub fn try_callback_call<C: SolCall>(
+        &mut self,
+        target: Address,
+        call: C,
+        context: &CallbackContext,
+        return_value: Bytes,
+        repayment: &Repayment,
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        let mut all_calls = callback_calls.unwrap_or_default();
+        let nested_len = all_calls.len();
+        all_calls.extend(repayment.calls());
+        let call = Self::try_build_callback_call(
+            target,
+            U256::ZERO,
+            call,
+            context,
+            &all_calls,
+            &return_value,
+        )?;
+        let nested = self.take_nested_into(&all_calls[..nested_len]);
+        self.insert_encoded(self.len(), call, U256::ZERO, nested);
+        Ok(self)
+    }
+
+    /// Like [`callback_call`](Self::callback_call), with the callback calls pushed by
+    /// `callback` onto a sub-encoder.
+    pub fn callback_call_with<C: SolCall>(
+        &mut self,
+        target: Address,
+        call: C,
+        context: &CallbackContext,
+        return_value: Bytes,
+        repayment: &Repayment,
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.callback_call(
+            target,
+            call,
+            context,
+            return_value,
+            repayment,
+            Some(callback_calls),
+        )
+    }
+
+    /// Fallible version of [`callback_call_with`](Self::callback_call_with).
+    ///
+    /// On error, the calls pushed by `callback` are dropped, along with their value.
+    pub fn try_callback_call_with<C: SolCall>(
+        &mut self,
+        target: Address,
+        call: C,
+        context: &CallbackContext,
+        return_value: Bytes,
+        repayment: &Repayment,
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> Result<&mut Self, Error> {
+        self.try_push_with_callback(
+            |inner| {
+                callback(inner);
+                Ok(())
+            },
+            |encoder, callback_calls| {
+                encoder.try_callback_call(
+                    target,
+                    call,
+                    context,
+                    return_value,
+                    repayment,
+                    Some(callback_calls),
+                )
+            },
+        )
+    }
+
     /// Executes a Balancer flash loan.
     ///
-    /// Callback data index = 3 (receiveFlashLoan has `bytes` at arg index 3).
+    /// Called back with [`RECEIVE_FLASH_LOAN`].
     /// Automatically appends transfer repayment calls.
     pub fn balancer_flash_loan(
         &mut self,
@@ -83,40 +354,39 @@ impl ExecutorEncoder {
         requests: &[AssetRequest],
         callback_calls: Option<Vec<Bytes>>,
     ) -> &mut Self {
-        let callback_calls = callback_calls.unwrap_or_default();
-
-        let repayment_calls: Vec<Bytes> = requests
-            .iter()
-            .map(|r| Self::build_erc20_transfer(r.asset, vault, r.amount))
-            .collect();
-
-        let mut all_calls = callback_calls;
-        all_calls.extend(repayment_calls);
-
-        let user_data = encode_callback_data(all_calls, Bytes::new());
-
-        let tokens: Vec<Address> = requests.iter().map(|r| r.asset).collect();
-        let amounts: Vec<U256> = requests.iter().map(|r| r.amount).collect();
+        self.push_balancer_flash_loan(vault, requests, callback_calls)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
 
-        let call_data = balancer_sol::flashLoanCall {
-            recipient: self.address(),
-            tokens,
-            amounts,
-            userData: user_data,
-        }
-        .abi_encode()
-        .into();
+    /// Like [`balancer_flash_loan`](Self::balancer_flash_loan), validating the requested
+    /// assets first.
+    ///
+    /// Fails if no asset is requested, or one is the zero address or requested twice.
+    pub fn try_balancer_flash_loan(
+        &mut self,
+        vault: Address,
+        requests: &[AssetRequest],
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        check_requests(requests.iter().map(|r| (r.asset, r.amount)))?;
+        self.push_balancer_flash_loan(vault, requests, callback_calls)
+    }
 
-        let ctx = CallbackContext {
-            sender: vault,
-            data_index: 3,
-        };
-        self.push_call(vault, U256::ZERO, call_data, Some(&ctx), vec![])
+    /// Like [`balancer_flash_loan`](Self::balancer_flash_loan), with the callback calls
+    /// pushed by `callback` onto a sub-encoder.
+    pub fn balancer_flash_loan_with(
+        &mut self,
+        vault: Address,
+        requests: &[AssetRequest],
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.balancer_flash_loan(vault, requests, Some(callback_calls))
     }
 
     /// Executes a Maker (ERC3156) flash loan.
     ///
-    /// Callback data index = 4 (onFlashLoan has `bytes` at arg index 4).
+    /// Called back with [`ON_FLASH_LOAN`].
     /// Returns `keccak256("ERC3156FlashBorrower.onFlashLoan")` as the callback return value.
     pub fn maker_flash_loan(
         &mut self,
@@ -125,37 +395,41 @@ impl ExecutorEncoder {
         amount: U256,
         callback_calls: Option<Vec<Bytes>>,
     ) -> &mut Self {
-        let callback_calls = callback_calls.unwrap_or_default();
-
-        let mut all_calls = callback_calls;
-        all_calls.push(Self::build_erc20_approve(asset, vault, amount));
-
-        let return_value = Bytes::from(
-            alloy_primitives::keccak256("ERC3156FlashBorrower.onFlashLoan")
-                .as_slice()
-                .to_vec(),
-        );
-        let data = encode_callback_data(all_calls, return_value);
+        self.push_maker_flash_loan(vault, asset, amount, callback_calls)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
 
-        let call_data = maker_sol::flashLoanCall {
-            receiver: self.address(),
-            token: asset,
-            amount,
-            data,
-        }
-        .abi_encode()
-        .into();
+    /// Like [`maker_flash_loan`](Self::maker_flash_loan), validating the requested
+    /// assets first.
+    ///
+    /// Fails if no asset is requested, or one is the zero address or requested twice.
+    pub fn try_maker_flash_loan(
+        &mut self,
+        vault: Address,
+        asset: Address,
+        amount: U256,
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        check_requests([(asset, amount)])?;
+        self.push_maker_flash_loan(vault, asset, amount, callback_calls)
+    }
 
-        let ctx = CallbackContext {
-            sender: vault,
-            data_index: 4,
-        };
-        self.push_call(vault, U256::ZERO, call_data, Some(&ctx), vec![])
+    /// Like [`maker_flash_loan`](Self::maker_flash_loan), with the callback calls
+    /// pushed by `callback` onto a sub-encoder.
+    pub fn maker_flash_loan_with(
+        &mut self,
+        vault: Address,
+        asset: Address,
+        amount: U256,
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.maker_flash_loan(vault, asset, amount, Some(callback_calls))
     }
 
     /// Executes an Aave flash loan.
     ///
-    /// Callback data index = 4 (executeOperation has `bytes` at arg index 4).
+    /// Called back with [`EXECUTE_OPERATION`].
     /// `premium` is the Aave flash loan fee in basis points (e.g., 5 = 0.05%).
     pub fn aave_flash_loan(
         &mut self,
@@ -164,58 +438,48 @@ impl ExecutorEncoder {
         premium: U256,
         callback_calls: Option<Vec<Bytes>>,
     ) -> &mut Self {
-        let callback_calls = callback_calls.unwrap_or_default();
-
-        let approval_calls: Vec<Bytes> = requests
-            .iter()
-            .map(|r| {
-                let approval_amount = r.amount + percent_mul(r.amount, premium);
-                Self::build_erc20_approve(r.asset, pool, approval_amount)
-            })
-            .collect();
-
-        let mut all_calls = callback_calls;
-        all_calls.extend(approval_calls);
+        self.push_aave_flash_loan(pool, requests, premium, callback_calls)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
 
-        // Return value: 0x0000...0001 (true, indicating successful execution)
-        let return_value = Bytes::from({
-            let mut b = vec![0u8; 32];
-            b[31] = 1;
-            b
-        });
-        let params = encode_callback_data(all_calls, return_value);
-
-        let assets: Vec<Address> = requests.iter().map(|r| r.asset).collect();
-        let amounts: Vec<U256> = requests.iter().map(|r| r.amount).collect();
-        let modes: Vec<U256> = requests.iter().map(|_| U256::ZERO).collect();
-
-        let call_data = aave_pool_sol::flashLoanCall {
-            receiverAddress: self.address(),
-            assets,
-            amounts,
-            modes,
-            onBehalfOf: self.address(),
-            params,
-            referralCode: 0,
-        }
-        .abi_encode()
-        .into();
+    /// Like [`aave_flash_loan`](Self::aave_flash_loan), validating the requested
+    /// assets first.
+    ///
+    /// Fails if no asset is requested, or one is the zero address or requested twice.
+    pub fn try_aave_flash_loan(
+        &mut self,
+        pool: Address,
+        requests: &[AssetRequest],
+        premium: U256,
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        check_requests(requests.iter().map(|r| (r.asset, r.amount)))?;
+        self.push_aave_flash_loan(pool, requests, premium, callback_calls)
+    }
 
-        let ctx = CallbackContext {
-            sender: pool,
-            data_index: 4,
-        };
-        self.push_call(pool, U256::ZERO, call_data, Some(&ctx), vec![])
+    /// Like [`aave_flash_loan`](Self::aave_flash_loan), with the callback calls
+    /// pushed by `callback` onto a sub-encoder.
+    pub fn aave_flash_loan_with(
+        &mut self,
+        pool: Address,
+        requests: &[AssetRequest],
+        premium: U256,
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.aave_flash_loan(pool, requests, premium, Some(callback_calls))
     }
 
     /// Executes a Uniswap V2 flash swap.
     ///
-    /// Callback data index = 3 (uniswapV2Call has `bytes` at arg index 3).
+    /// Called back with [`UNISWAP_V2_CALL`].
+    ///
+    /// Approves each loan plus the pair's 0.3% fee, charged on the amount paid back: the
+    /// fee on `amount` is `amount * 3 / 997`, rounded up.
     ///
     /// **Warning:** This method currently uses the UniV3 `flash` function signature
-    /// instead of the V2 pair's `swap`. Fee calculation is also unimplemented (hardcoded to 0).
-    /// This matches the upstream TS implementation which has the same TODO.
-    /// Do not use on real V2 pools without fixing these issues.
+    /// instead of the V2 pair's `swap`, as the upstream TS implementation does.
+    /// Do not use on real V2 pools without fixing this.
     pub fn uni_v2_flash_swap(
         &mut self,
         pool: Address,
@@ -223,45 +487,41 @@ impl ExecutorEncoder {
         amounts: [U256; 2],
         callback_calls: Option<Vec<Bytes>>,
     ) -> &mut Self {
-        let callback_calls = callback_calls.unwrap_or_default();
-
-        // TODO: calculate fee
-        let fee0 = U256::ZERO;
-        let fee1 = U256::ZERO;
-
-        let mut all_calls = callback_calls;
-        all_calls.push(Self::build_erc20_approve(
-            assets[0],
-            pool,
-            amounts[0] + fee0,
-        ));
-        all_calls.push(Self::build_erc20_approve(
-            assets[1],
-            pool,
-            amounts[1] + fee1,
-        ));
-
-        let data = encode_callback_data(all_calls, Bytes::new());
+        self.push_uni_v2_flash_swap(pool, assets, amounts, callback_calls)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
 
-        let call_data = uni_flash_sol::flashCall {
-            receiver: self.address(),
-            amount0: amounts[0],
-            amount1: amounts[1],
-            data,
-        }
-        .abi_encode()
-        .into();
+    /// Like [`uni_v2_flash_swap`](Self::uni_v2_flash_swap), validating the requested
+    /// assets first.
+    ///
+    /// Fails if no asset is requested, or one is the zero address or requested twice.
+    pub fn try_uni_v2_flash_swap(
+        &mut self,
+        pool: Address,
+        assets: [Address; 2],
+        amounts: [U256; 2],
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        check_requests(assets.into_iter().zip(amounts))?;
+        self.push_uni_v2_flash_swap(pool, assets, amounts, callback_calls)
+    }
 
-        let ctx = CallbackContext {
-            sender: pool,
-            data_index: 3,
-        };
-        self.push_call(pool, U256::ZERO, call_data, Some(&ctx), vec![])
+    /// Like [`uni_v2_flash_swap`](Self::uni_v2_flash_swap), with the callback calls
+    /// pushed by `callback` onto a sub-encoder.
+    pub fn uni_v2_flash_swap_with(
+        &mut self,
+        pool: Address,
+        assets: [Address; 2],
+        amounts: [U256; 2],
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.uni_v2_flash_swap(pool, assets, amounts, Some(callback_calls))
     }
 
     /// Executes a Uniswap V3 flash loan.
     ///
-    /// Callback data index = 2 (uniswapV3FlashCallback has `bytes` at arg index 2).
+    /// Called back with [`UNISWAP_V3_FLASH_CALLBACK`].
     /// `fee` is in basis points (e.g., 500 = 0.05%).
     pub fn uni_v3_flash_loan(
         &mut self,
@@ -271,70 +531,472 @@ impl ExecutorEncoder {
         fee: U256,
         callback_calls: Option<Vec<Bytes>>,
     ) -> &mut Self {
-        let callback_calls = callback_calls.unwrap_or_default();
+        self.push_uni_v3_flash_loan(pool, assets, amounts, fee, callback_calls)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
 
-        let fee0 = mul_div_up(amounts[0], fee, U256::from(1_000_000u64));
-        let fee1 = mul_div_up(amounts[1], fee, U256::from(1_000_000u64));
+    /// Like [`uni_v3_flash_loan`](Self::uni_v3_flash_loan), validating the requested
+    /// assets first.
+    ///
+    /// Fails if no asset is requested, or one is the zero address or requested twice.
+    pub fn try_uni_v3_flash_loan(
+        &mut self,
+        pool: Address,
+        assets: [Address; 2],
+        amounts: [U256; 2],
+        fee: U256,
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        check_requests(assets.into_iter().zip(amounts))?;
+        self.push_uni_v3_flash_loan(pool, assets, amounts, fee, callback_calls)
+    }
 
-        let mut all_calls = callback_calls;
-        all_calls.push(Self::build_erc20_transfer(
-            assets[0],
-            pool,
-            amounts[0] + fee0,
-        ));
-        all_calls.push(Self::build_erc20_transfer(
-            assets[1],
-            pool,
-            amounts[1] + fee1,
-        ));
+    /// Like [`uni_v3_flash_loan`](Self::uni_v3_flash_loan), with the callback calls
+    /// pushed by `callback` onto a sub-encoder.
+    pub fn uni_v3_flash_loan_with(
+        &mut self,
+        pool: Address,
+        assets: [Address; 2],
+        amounts: [U256; 2],
+        fee: U256,
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.uni_v3_flash_loan(pool, assets, amounts, fee, Some(callback_calls))
+    }
+
+    /// Executes a Morpho Blue flash loan.
+    ///
+    /// Called back with [`ON_MORPHO_FLASH_LOAN`].
+    pub fn blue_flash_loan(
+        &mut self,
+        morpho_blue: Address,
+        asset: Address,
+        amount: U256,
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> &mut Self {
+        self.push_blue_flash_loan(morpho_blue, asset, amount, callback_calls)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Like [`blue_flash_loan`](Self::blue_flash_loan), validating the requested
+    /// assets first.
+    ///
+    /// Fails if no asset is requested, or one is the zero address or requested twice.
+    pub fn try_blue_flash_loan(
+        &mut self,
+        morpho_blue: Address,
+        asset: Address,
+        amount: U256,
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        check_requests([(asset, amount)])?;
+        self.push_blue_flash_loan(morpho_blue, asset, amount, callback_calls)
+    }
 
-        let data = encode_callback_data(all_calls, Bytes::new());
+    /// Like [`blue_flash_loan`](Self::blue_flash_loan), with the callback calls
+    /// pushed by `callback` onto a sub-encoder.
+    pub fn blue_flash_loan_with(
+        &mut self,
+        morpho_blue: Address,
+        asset: Address,
+        amount: U256,
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.blue_flash_loan(morpho_blue, asset, amount, Some(callback_calls))
+    }
 
-        let call_data = uni_flash_sol::flashCall {
+    /// Pushes a Balancer flash loan, without validating the requested assets.
+    fn push_balancer_flash_loan(
+        &mut self,
+        vault: Address,
+        requests: &[AssetRequest],
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        let call = balancer_sol::flashLoanCall {
+            recipient: self.address(),
+            tokens: requests.iter().map(|r| r.asset).collect(),
+            amounts: requests.iter().map(|r| r.amount).collect(),
+            userData: Bytes::new(),
+        };
+
+        let repayment = Repayment::transfer(vault, requests.to_vec());
+        self.try_callback_call(
+            vault,
+            call,
+            &RECEIVE_FLASH_LOAN.context(vault),
+            Bytes::new(),
+            &repayment,
+            callback_calls,
+        )
+    }
+
+    /// Pushes a Maker flash loan, without validating the requested assets.
+    fn push_maker_flash_loan(
+        &mut self,
+        vault: Address,
+        asset: Address,
+        amount: U256,
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        let call = maker_sol::flashLoanCall {
+            receiver: self.address(),
+            token: asset,
+            amount,
+            data: Bytes::new(),
+        };
+
+        let return_value = Bytes::from(
+            alloy_primitives::keccak256("ERC3156FlashBorrower.onFlashLoan")
+                .as_slice()
+                .to_vec(),
+        );
+        let repayment = Repayment::approve(vault, vec![AssetRequest { asset, amount }]);
+        self.try_callback_call(
+            vault,
+            call,
+            &ON_FLASH_LOAN.context(vault),
+            return_value,
+            &repayment,
+            callback_calls,
+        )
+    }
+
+    /// Pushes a Aave flash loan, without validating the requested assets.
+    fn push_aave_flash_loan(
+        &mut self,
+        pool: Address,
+        requests: &[AssetRequest],
+        premium: U256,
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        let call = aave_pool_sol::flashLoanCall {
+            receiverAddress: self.address(),
+            assets: requests.iter().map(|r| r.asset).collect(),
+            amounts: requests.iter().map(|r| r.amount).collect(),
+            modes: vec![U256::ZERO; requests.len()],
+            onBehalfOf: self.address(),
+            params: Bytes::new(),
+            referralCode: 0,
+        };
+
+        // Return value: 0x0000...0001 (true, indicating successful execution)
+        let return_value = Bytes::from(U256::from(1).to_be_bytes_vec());
+        let repayment = Repayment::approve(pool, requests.to_vec()).with_fee(Fee::Percent(premium));
+        self.try_callback_call(
+            pool,
+            call,
+            &EXECUTE_OPERATION.context(pool),
+            return_value,
+            &repayment,
+            callback_calls,
+        )
+    }
+
+    /// Pushes a Uniswap V2 flash swap, without validating the requested assets.
+    fn push_uni_v2_flash_swap(
+        &mut self,
+        pool: Address,
+        assets: [Address; 2],
+        amounts: [U256; 2],
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        let call = uni_flash_sol::flashCall {
             receiver: self.address(),
             amount0: amounts[0],
             amount1: amounts[1],
-            data,
-        }
-        .abi_encode()
-        .into();
+            data: Bytes::new(),
+        };
+
+        let fee = Fee::Custom(Arc::new(|amount| {
+            mul_div_up(amount, U256::from(3), U256::from(997))
+        }));
+        let repayment = Repayment::approve(pool, loans(assets, amounts)).with_fee(fee);
+        self.try_callback_call(
+            pool,
+            call,
+            &UNISWAP_V2_CALL.context(pool),
+            Bytes::new(),
+            &repayment,
+            callback_calls,
+        )
+    }
 
-        let ctx = CallbackContext {
-            sender: pool,
-            data_index: 2,
+    /// Pushes a Uniswap V3 flash loan, without validating the requested assets.
+    fn push_uni_v3_flash_loan(
+        &mut self,
+        pool: Address,
+        assets: [Address; 2],
+        amounts: [U256; 2],
+        fee: U256,
+        callback_calls: Option<Vec<Bytes>>,
+    ) -> Result<&mut Self, Error> {
+        let call = uni_flash_sol::flashCall {
+            receiver: self.address(),
+            amount0: amounts[0],
+            amount1: amounts[1],
+            data: Bytes::new(),
         };
-        self.push_call(pool, U256::ZERO, call_data, Some(&ctx), vec![])
+
+        let repayment = Repayment::transfer(pool, loans(assets, amounts)).with_fee(Fee::Pips(fee));
+        self.try_callback_call(
+            pool,
+            call,
+            &UNISWAP_V3_FLASH_CALLBACK.context(pool),
+            Bytes::new(),
+            &repayment,
+            callback_calls,
+        )
     }
 
-    /// Executes a Morpho Blue flash loan.
-    ///
-    /// Callback data index = 1 (onMorphoFlashLoan has `bytes` at arg index 1).
-    pub fn blue_flash_loan(
+    /// Pushes a Morpho Blue flash loan, without validating the requested assets.
+    fn push_blue_flash_loan(
         &mut self,
         morpho_blue: Address,
         asset: Address,
         amount: U256,
         callback_calls: Option<Vec<Bytes>>,
-    ) -> &mut Self {
-        let callback_calls = callback_calls.unwrap_or_default();
+    ) -> Result<&mut Self, Error> {
+        let call = morpho_blue_flash_sol::flashLoanCall {
+            asset,
+            amount,
+            data: Bytes::new(),
+        };
 
-        let mut all_calls = callback_calls;
-        all_calls.push(Self::build_erc20_approve(asset, morpho_blue, amount));
+        let repayment = Repayment::approve(morpho_blue, vec![AssetRequest { asset, amount }]);
+        self.try_callback_call(
+            morpho_blue,
+            call,
+            &ON_MORPHO_FLASH_LOAN.context(morpho_blue),
+            Bytes::new(),
+            &repayment,
+            callback_calls,
+        )
+    }
+}
 
-        let data = encode_callback_data(all_calls, Bytes::new());
+/// Returns the assets actually borrowed by a flash loan, which it lends and takes back.
+fn borrowed(requests: impl IntoIterator<Item = (Address, U256)>) -> Vec<Address> {
+    requests
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(asset, _)| asset)
+        .collect()
+}
 
-        let call_data = morpho_blue_flash_sol::flashLoanCall {
-            asset,
-            amount,
-            data,
-        }
-        .abi_encode()
-        .into();
+/// A Balancer flash loan, see [`ExecutorEncoder::balancer_flash_loan`].
+#[derive(Debug)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct BalancerFlashLoan {
+    pub vault: Address,
+    pub requests: Vec<AssetRequest>,
+    #[cfg_attr(feature = "strategy", serde(default))]
+    pub callback: Vec<Box<dyn Action>>,
+}
 
-        let ctx = CallbackContext {
-            sender: morpho_blue,
-            data_index: 1,
-        };
-        self.push_call(morpho_blue, U256::ZERO, call_data, Some(&ctx), vec![])
+impl Action for BalancerFlashLoan {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encode_with_callback(encoder, &self.callback, |encoder, callback_calls| {
+            encoder.try_balancer_flash_loan(self.vault, &self.requests, callback_calls)
+        })
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        borrowed(self.requests.iter().map(|r| (r.asset, r.amount)))
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        self.consumes()
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &self.callback
+    }
+}
+
+/// A Maker (ERC3156) flash loan, see [`ExecutorEncoder::maker_flash_loan`].
+#[derive(Debug)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct MakerFlashLoan {
+    pub vault: Address,
+    pub asset: Address,
+    pub amount: U256,
+    #[cfg_attr(feature = "strategy", serde(default))]
+    pub callback: Vec<Box<dyn Action>>,
+}
+
+impl Action for MakerFlashLoan {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encode_with_callback(encoder, &self.callback, |encoder, callback_calls| {
+            encoder.try_maker_flash_loan(self.vault, self.asset, self.amount, callback_calls)
+        })
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        borrowed([(self.asset, self.amount)])
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        self.consumes()
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &self.callback
+    }
+}
+
+/// An Aave flash loan, see [`ExecutorEncoder::aave_flash_loan`].
+#[derive(Debug)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct AaveFlashLoan {
+    pub pool: Address,
+    pub requests: Vec<AssetRequest>,
+    pub premium: U256,
+    #[cfg_attr(feature = "strategy", serde(default))]
+    pub callback: Vec<Box<dyn Action>>,
+}
+
+impl Action for AaveFlashLoan {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encode_with_callback(encoder, &self.callback, |encoder, callback_calls| {
+            encoder.try_aave_flash_loan(self.pool, &self.requests, self.premium, callback_calls)
+        })
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        borrowed(self.requests.iter().map(|r| (r.asset, r.amount)))
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        self.consumes()
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &self.callback
+    }
+}
+
+/// A UniswapV2 flash swap, see [`ExecutorEncoder::uni_v2_flash_swap`].
+#[derive(Debug)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct UniV2FlashSwap {
+    pub pool: Address,
+    pub assets: [Address; 2],
+    pub amounts: [U256; 2],
+    #[cfg_attr(feature = "strategy", serde(default))]
+    pub callback: Vec<Box<dyn Action>>,
+}
+
+impl Action for UniV2FlashSwap {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encode_with_callback(encoder, &self.callback, |encoder, callback_calls| {
+            encoder.try_uni_v2_flash_swap(self.pool, self.assets, self.amounts, callback_calls)
+        })
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        borrowed(self.assets.into_iter().zip(self.amounts))
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        self.consumes()
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &self.callback
+    }
+}
+
+/// A UniswapV3 flash loan, see [`ExecutorEncoder::uni_v3_flash_loan`].
+#[derive(Debug)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct UniV3FlashLoan {
+    pub pool: Address,
+    pub assets: [Address; 2],
+    pub amounts: [U256; 2],
+    pub fee: U256,
+    #[cfg_attr(feature = "strategy", serde(default))]
+    pub callback: Vec<Box<dyn Action>>,
+}
+
+impl Action for UniV3FlashLoan {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encode_with_callback(encoder, &self.callback, |encoder, callback_calls| {
+            encoder.try_uni_v3_flash_loan(
+                self.pool,
+                self.assets,
+                self.amounts,
+                self.fee,
+                callback_calls,
+            )
+        })
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        borrowed(self.assets.into_iter().zip(self.amounts))
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        self.consumes()
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &self.callback
+    }
+}
+
+/// A Morpho Blue flash loan, see [`ExecutorEncoder::blue_flash_loan`].
+#[derive(Debug)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct BlueFlashLoan {
+    pub morpho_blue: Address,
+    pub asset: Address,
+    pub amount: U256,
+    #[cfg_attr(feature = "strategy", serde(default))]
+    pub callback: Vec<Box<dyn Action>>,
+}
+
+impl Action for BlueFlashLoan {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encode_with_callback(encoder, &self.callback, |encoder, callback_calls| {
+            encoder.try_blue_flash_loan(self.morpho_blue, self.asset, self.amount, callback_calls)
+        })
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        borrowed([(self.asset, self.amount)])
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        self.consumes()
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &self.callback
     }
 }
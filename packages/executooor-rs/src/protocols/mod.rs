@@ -6,5 +6,7 @@ pub mod erc4626;
 pub mod flashloans;
 pub mod morpho;
 pub mod morpho_blue;
+pub mod multicall3;
+pub mod uniswap_v2;
 pub mod uniswap_v3;
 pub mod weth;
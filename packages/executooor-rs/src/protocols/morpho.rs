@@ -3,7 +3,7 @@ use alloy_sol_types::SolCall;
 
 use crate::encoder::ExecutorEncoder;
 
-mod morpho_sol {
+pub(crate) mod morpho_sol {
     use alloy_sol_types::sol;
 
     sol! {
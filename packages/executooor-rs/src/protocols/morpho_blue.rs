@@ -1,10 +1,16 @@
 use alloy_primitives::{Address, Bytes, U256};
 use alloy_sol_types::SolCall;
 
-use crate::encoder::{encode_callback_data, ExecutorEncoder};
-use crate::types::{CallbackContext, MarketParams};
+use crate::action::{encode_callback, Action};
+use crate::callbacks::{
+    ON_MORPHO_LIQUIDATE, ON_MORPHO_REPAY, ON_MORPHO_SUPPLY, ON_MORPHO_SUPPLY_COLLATERAL,
+};
+use crate::encoder::ExecutorEncoder;
+use crate::error::Error;
+use crate::types::MarketParams;
+use crate::Placeholder;
 
-mod morpho_blue_sol {
+pub(crate) mod morpho_blue_sol {
     use alloy_sol_types::sol;
 
     sol! {
@@ -62,6 +68,12 @@ mod morpho_blue_sol {
             address receiver
         ) external;
 
+        function position(bytes32 id, address user) external view returns (
+            uint256 supplyShares,
+            uint128 borrowShares,
+            uint128 collateral
+        );
+
         function liquidate(
             MarketParams marketParams,
             address borrower,
@@ -82,10 +94,38 @@ fn to_sol_market(m: &MarketParams) -> morpho_blue_sol::MarketParams {
     }
 }
 
+/// A field of the Morpho Blue `position` return tuple.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MorphoBluePosition {
+    SupplyShares = 0,
+    BorrowShares = 1,
+    Collateral = 2,
+}
+
 impl ExecutorEncoder {
+    /// Creates a `Placeholder` that reads `field` of `user`'s position in `market`.
+    pub fn morpho_blue_position(
+        &self,
+        morpho_blue: Address,
+        market: &MarketParams,
+        user: Address,
+        field: MorphoBluePosition,
+        offset: u64,
+    ) -> Placeholder {
+        self.view_placeholder(
+            morpho_blue,
+            morpho_blue_sol::positionCall {
+                id: market.id(),
+                user,
+            },
+            field as u64,
+            offset,
+        )
+    }
+
     /// Supplies collateral to Morpho Blue.
     ///
-    /// Callback data index = 1 (onMorphoSupplyCollateral).
+    /// Called back with [`ON_MORPHO_SUPPLY_COLLATERAL`].
     pub fn morpho_blue_supply_collateral(
         &mut self,
         morpho_blue: Address,
@@ -95,22 +135,43 @@ impl ExecutorEncoder {
         callback_calls: Option<Vec<Bytes>>,
     ) -> &mut Self {
         let callback_calls = callback_calls.unwrap_or_default();
-        let data = encode_callback_data(callback_calls, Bytes::new());
 
-        let call_data = morpho_blue_sol::supplyCollateralCall {
+        let call = morpho_blue_sol::supplyCollateralCall {
             marketParams: to_sol_market(market),
             assets: collateral,
             onBehalf: on_behalf,
-            data,
-        }
-        .abi_encode()
-        .into();
-
-        let ctx = CallbackContext {
-            sender: morpho_blue,
-            data_index: 1,
+            data: Bytes::new(),
         };
-        self.push_call(morpho_blue, U256::ZERO, call_data, Some(&ctx), vec![])
+
+        let ctx = ON_MORPHO_SUPPLY_COLLATERAL.context(morpho_blue);
+        self.push_callback_call(
+            morpho_blue,
+            U256::ZERO,
+            call,
+            &ctx,
+            callback_calls,
+            Bytes::new(),
+        )
+    }
+
+    /// Like [`morpho_blue_supply_collateral`](Self::morpho_blue_supply_collateral), with the callback calls
+    /// pushed by `callback` onto a sub-encoder.
+    pub fn morpho_blue_supply_collateral_with(
+        &mut self,
+        morpho_blue: Address,
+        market: &MarketParams,
+        collateral: U256,
+        on_behalf: Address,
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.morpho_blue_supply_collateral(
+            morpho_blue,
+            market,
+            collateral,
+            on_behalf,
+            Some(callback_calls),
+        )
     }
 
     /// Withdraws collateral from Morpho Blue.
@@ -135,7 +196,7 @@ impl ExecutorEncoder {
 
     /// Supplies to Morpho Blue.
     ///
-    /// Callback data index = 1 (onMorphoSupply).
+    /// Called back with [`ON_MORPHO_SUPPLY`].
     pub fn morpho_blue_supply(
         &mut self,
         morpho_blue: Address,
@@ -146,23 +207,46 @@ impl ExecutorEncoder {
         callback_calls: Option<Vec<Bytes>>,
     ) -> &mut Self {
         let callback_calls = callback_calls.unwrap_or_default();
-        let data = encode_callback_data(callback_calls, Bytes::new());
 
-        let call_data = morpho_blue_sol::supplyCall {
+        let call = morpho_blue_sol::supplyCall {
             marketParams: to_sol_market(market),
             assets,
             shares,
             onBehalf: on_behalf,
-            data,
-        }
-        .abi_encode()
-        .into();
-
-        let ctx = CallbackContext {
-            sender: morpho_blue,
-            data_index: 1,
+            data: Bytes::new(),
         };
-        self.push_call(morpho_blue, U256::ZERO, call_data, Some(&ctx), vec![])
+
+        let ctx = ON_MORPHO_SUPPLY.context(morpho_blue);
+        self.push_callback_call(
+            morpho_blue,
+            U256::ZERO,
+            call,
+            &ctx,
+            callback_calls,
+            Bytes::new(),
+        )
+    }
+
+    /// Like [`morpho_blue_supply`](Self::morpho_blue_supply), with the callback calls
+    /// pushed by `callback` onto a sub-encoder.
+    pub fn morpho_blue_supply_with(
+        &mut self,
+        morpho_blue: Address,
+        market: &MarketParams,
+        assets: U256,
+        shares: U256,
+        on_behalf: Address,
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.morpho_blue_supply(
+            morpho_blue,
+            market,
+            assets,
+            shares,
+            on_behalf,
+            Some(callback_calls),
+        )
     }
 
     /// Withdraws from Morpho Blue.
@@ -189,7 +273,7 @@ impl ExecutorEncoder {
 
     /// Repays to Morpho Blue.
     ///
-    /// Callback data index = 1 (onMorphoRepay).
+    /// Called back with [`ON_MORPHO_REPAY`].
     pub fn morpho_blue_repay(
         &mut self,
         morpho_blue: Address,
@@ -200,23 +284,46 @@ impl ExecutorEncoder {
         callback_calls: Option<Vec<Bytes>>,
     ) -> &mut Self {
         let callback_calls = callback_calls.unwrap_or_default();
-        let data = encode_callback_data(callback_calls, Bytes::new());
 
-        let call_data = morpho_blue_sol::repayCall {
+        let call = morpho_blue_sol::repayCall {
             marketParams: to_sol_market(market),
             assets,
             shares,
             onBehalf: on_behalf,
-            data,
-        }
-        .abi_encode()
-        .into();
-
-        let ctx = CallbackContext {
-            sender: morpho_blue,
-            data_index: 1,
+            data: Bytes::new(),
         };
-        self.push_call(morpho_blue, U256::ZERO, call_data, Some(&ctx), vec![])
+
+        let ctx = ON_MORPHO_REPAY.context(morpho_blue);
+        self.push_callback_call(
+            morpho_blue,
+            U256::ZERO,
+            call,
+            &ctx,
+            callback_calls,
+            Bytes::new(),
+        )
+    }
+
+    /// Like [`morpho_blue_repay`](Self::morpho_blue_repay), with the callback calls
+    /// pushed by `callback` onto a sub-encoder.
+    pub fn morpho_blue_repay_with(
+        &mut self,
+        morpho_blue: Address,
+        market: &MarketParams,
+        assets: U256,
+        shares: U256,
+        on_behalf: Address,
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.morpho_blue_repay(
+            morpho_blue,
+            market,
+            assets,
+            shares,
+            on_behalf,
+            Some(callback_calls),
+        )
     }
 
     /// Borrows from Morpho Blue.
@@ -243,7 +350,7 @@ impl ExecutorEncoder {
 
     /// Liquidates a position on Morpho Blue.
     ///
-    /// Callback data index = 1 (onMorphoLiquidate).
+    /// Called back with [`ON_MORPHO_LIQUIDATE`].
     pub fn morpho_blue_liquidate(
         &mut self,
         morpho_blue: Address,
@@ -254,22 +361,307 @@ impl ExecutorEncoder {
         callback_calls: Option<Vec<Bytes>>,
     ) -> &mut Self {
         let callback_calls = callback_calls.unwrap_or_default();
-        let data = encode_callback_data(callback_calls, Bytes::new());
 
-        let call_data = morpho_blue_sol::liquidateCall {
+        let call = morpho_blue_sol::liquidateCall {
             marketParams: to_sol_market(market),
             borrower,
             seizedAssets: seized_assets,
             repaidShares: repaid_shares,
-            data,
-        }
-        .abi_encode()
-        .into();
-
-        let ctx = CallbackContext {
-            sender: morpho_blue,
-            data_index: 1,
+            data: Bytes::new(),
         };
-        self.push_call(morpho_blue, U256::ZERO, call_data, Some(&ctx), vec![])
+
+        let ctx = ON_MORPHO_LIQUIDATE.context(morpho_blue);
+        self.push_callback_call(
+            morpho_blue,
+            U256::ZERO,
+            call,
+            &ctx,
+            callback_calls,
+            Bytes::new(),
+        )
+    }
+
+    /// Like [`morpho_blue_liquidate`](Self::morpho_blue_liquidate), with the callback calls
+    /// pushed by `callback` onto a sub-encoder.
+    pub fn morpho_blue_liquidate_with(
+        &mut self,
+        morpho_blue: Address,
+        market: &MarketParams,
+        borrower: Address,
+        seized_assets: U256,
+        repaid_shares: U256,
+        callback: impl FnOnce(&mut ExecutorEncoder),
+    ) -> &mut Self {
+        let callback_calls = self.build_callback(callback);
+        self.morpho_blue_liquidate(
+            morpho_blue,
+            market,
+            borrower,
+            seized_assets,
+            repaid_shares,
+            Some(callback_calls),
+        )
+    }
+}
+
+/// Supplies collateral, see [`ExecutorEncoder::morpho_blue_supply_collateral`].
+#[derive(Debug)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct MorphoBlueSupplyCollateral {
+    pub morpho_blue: Address,
+    pub market: MarketParams,
+    pub collateral: U256,
+    pub on_behalf: Address,
+    #[cfg_attr(feature = "strategy", serde(default))]
+    pub callback: Vec<Box<dyn Action>>,
+}
+
+impl Action for MorphoBlueSupplyCollateral {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        let callback_calls = encode_callback(encoder, &self.callback)?;
+        encoder.morpho_blue_supply_collateral(
+            self.morpho_blue,
+            &self.market,
+            self.collateral,
+            self.on_behalf,
+            callback_calls,
+        );
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.market.collateral_token]
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &self.callback
+    }
+}
+
+/// Withdraws collateral, see [`ExecutorEncoder::morpho_blue_withdraw_collateral`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct MorphoBlueWithdrawCollateral {
+    pub morpho_blue: Address,
+    pub market: MarketParams,
+    pub collateral: U256,
+    pub on_behalf: Address,
+    pub receiver: Address,
+}
+
+impl Action for MorphoBlueWithdrawCollateral {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.morpho_blue_withdraw_collateral(
+            self.morpho_blue,
+            &self.market,
+            self.collateral,
+            self.on_behalf,
+            self.receiver,
+        );
+        Ok(())
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.market.collateral_token]
+    }
+}
+
+/// Supplies loan tokens, see [`ExecutorEncoder::morpho_blue_supply`].
+#[derive(Debug)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct MorphoBlueSupply {
+    pub morpho_blue: Address,
+    pub market: MarketParams,
+    pub assets: U256,
+    pub shares: U256,
+    pub on_behalf: Address,
+    #[cfg_attr(feature = "strategy", serde(default))]
+    pub callback: Vec<Box<dyn Action>>,
+}
+
+impl Action for MorphoBlueSupply {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        let callback_calls = encode_callback(encoder, &self.callback)?;
+        encoder.morpho_blue_supply(
+            self.morpho_blue,
+            &self.market,
+            self.assets,
+            self.shares,
+            self.on_behalf,
+            callback_calls,
+        );
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.market.loan_token]
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &self.callback
+    }
+}
+
+/// Withdraws loan tokens, see [`ExecutorEncoder::morpho_blue_withdraw`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct MorphoBlueWithdraw {
+    pub morpho_blue: Address,
+    pub market: MarketParams,
+    pub assets: U256,
+    pub shares: U256,
+    pub on_behalf: Address,
+    pub receiver: Address,
+}
+
+impl Action for MorphoBlueWithdraw {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.morpho_blue_withdraw(
+            self.morpho_blue,
+            &self.market,
+            self.assets,
+            self.shares,
+            self.on_behalf,
+            self.receiver,
+        );
+        Ok(())
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.market.loan_token]
+    }
+}
+
+/// Repays a debt, see [`ExecutorEncoder::morpho_blue_repay`].
+#[derive(Debug)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct MorphoBlueRepay {
+    pub morpho_blue: Address,
+    pub market: MarketParams,
+    pub assets: U256,
+    pub shares: U256,
+    pub on_behalf: Address,
+    #[cfg_attr(feature = "strategy", serde(default))]
+    pub callback: Vec<Box<dyn Action>>,
+}
+
+impl Action for MorphoBlueRepay {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        let callback_calls = encode_callback(encoder, &self.callback)?;
+        encoder.morpho_blue_repay(
+            self.morpho_blue,
+            &self.market,
+            self.assets,
+            self.shares,
+            self.on_behalf,
+            callback_calls,
+        );
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.market.loan_token]
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &self.callback
+    }
+}
+
+/// Borrows loan tokens, see [`ExecutorEncoder::morpho_blue_borrow`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct MorphoBlueBorrow {
+    pub morpho_blue: Address,
+    pub market: MarketParams,
+    pub assets: U256,
+    pub shares: U256,
+    pub on_behalf: Address,
+    pub receiver: Address,
+}
+
+impl Action for MorphoBlueBorrow {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.morpho_blue_borrow(
+            self.morpho_blue,
+            &self.market,
+            self.assets,
+            self.shares,
+            self.on_behalf,
+            self.receiver,
+        );
+        Ok(())
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.market.loan_token]
+    }
+}
+
+/// Liquidates `borrower`, see [`ExecutorEncoder::morpho_blue_liquidate`].
+#[derive(Debug)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct MorphoBlueLiquidate {
+    pub morpho_blue: Address,
+    pub market: MarketParams,
+    pub borrower: Address,
+    pub seized_assets: U256,
+    pub repaid_shares: U256,
+    #[cfg_attr(feature = "strategy", serde(default))]
+    pub callback: Vec<Box<dyn Action>>,
+}
+
+impl Action for MorphoBlueLiquidate {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        let callback_calls = encode_callback(encoder, &self.callback)?;
+        encoder.morpho_blue_liquidate(
+            self.morpho_blue,
+            &self.market,
+            self.borrower,
+            self.seized_assets,
+            self.repaid_shares,
+            callback_calls,
+        );
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.market.loan_token]
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.market.collateral_token]
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        &self.callback
     }
 }
@@ -0,0 +1,71 @@
+use alloy_primitives::{address, Address};
+
+use crate::encoder::ExecutorEncoder;
+use crate::Placeholder;
+
+/// Multicall3 address, the same on most EVM chains.
+///
+/// Its views expose block data and native balances to placeholders, which can only
+/// inject the results of static calls.
+pub const MULTICALL3: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+pub(crate) mod multicall3_sol {
+    use alloy_sol_types::sol;
+
+    sol! {
+        function getCurrentBlockTimestamp() external view returns (uint256 timestamp);
+        function getBasefee() external view returns (uint256 basefee);
+        function getEthBalance(address addr) external view returns (uint256 balance);
+        function getBlockNumber() external view returns (uint256 blockNumber);
+        function getCurrentBlockCoinbase() external view returns (address coinbase);
+    }
+}
+
+/// Placeholders reading block data and native balances at execution time from the
+/// Multicall3 deployed at `multicall`, usually [`MULTICALL3`].
+impl ExecutorEncoder {
+    /// Creates a `Placeholder` that reads `block.timestamp`.
+    pub fn multicall3_block_timestamp(&self, multicall: Address, offset: u64) -> Placeholder {
+        self.view_placeholder(
+            multicall,
+            multicall3_sol::getCurrentBlockTimestampCall {},
+            0,
+            offset,
+        )
+    }
+
+    /// Creates a `Placeholder` that reads `block.basefee`.
+    pub fn multicall3_basefee(&self, multicall: Address, offset: u64) -> Placeholder {
+        self.view_placeholder(multicall, multicall3_sol::getBasefeeCall {}, 0, offset)
+    }
+
+    /// Creates a `Placeholder` that reads the ETH balance of `owner`.
+    pub fn multicall3_eth_balance(
+        &self,
+        multicall: Address,
+        owner: Address,
+        offset: u64,
+    ) -> Placeholder {
+        self.view_placeholder(
+            multicall,
+            multicall3_sol::getEthBalanceCall { addr: owner },
+            0,
+            offset,
+        )
+    }
+
+    /// Creates a `Placeholder` that reads `block.number`.
+    pub fn multicall3_block_number(&self, multicall: Address, offset: u64) -> Placeholder {
+        self.view_placeholder(multicall, multicall3_sol::getBlockNumberCall {}, 0, offset)
+    }
+
+    /// Creates a `Placeholder` that reads `block.coinbase`.
+    pub fn multicall3_coinbase(&self, multicall: Address, offset: u64) -> Placeholder {
+        self.view_placeholder(
+            multicall,
+            multicall3_sol::getCurrentBlockCoinbaseCall {},
+            0,
+            offset,
+        )
+    }
+}
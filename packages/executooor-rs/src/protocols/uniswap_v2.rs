@@ -0,0 +1,36 @@
+use alloy_primitives::Address;
+
+use crate::encoder::ExecutorEncoder;
+use crate::Placeholder;
+
+pub(crate) mod uniswap_v2_sol {
+    use alloy_sol_types::sol;
+
+    sol! {
+        function getReserves() external view returns (
+            uint112 reserve0,
+            uint112 reserve1,
+            uint32 blockTimestampLast
+        );
+    }
+}
+
+/// A field of the UniswapV2 pair `getReserves` return tuple.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniV2Reserves {
+    Reserve0 = 0,
+    Reserve1 = 1,
+    BlockTimestampLast = 2,
+}
+
+impl ExecutorEncoder {
+    /// Creates a `Placeholder` that reads `field` of `getReserves()` from the pair.
+    pub fn uni_v2_reserves(&self, pair: Address, field: UniV2Reserves, offset: u64) -> Placeholder {
+        self.view_placeholder(
+            pair,
+            uniswap_v2_sol::getReservesCall {},
+            field as u64,
+            offset,
+        )
+    }
+}
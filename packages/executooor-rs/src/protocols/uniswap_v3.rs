@@ -1,7 +1,9 @@
 use alloy_primitives::{Address, Bytes, U256};
 use alloy_sol_types::{sol, SolCall};
 
+use crate::action::Action;
 use crate::encoder::ExecutorEncoder;
+use crate::error::Error;
 
 sol! {
     struct ExactInputParams {
@@ -12,6 +14,7 @@ sol! {
         uint256 amountOutMinimum;
     }
 
+    #[sol(abi)]
     function exactInput(ExactInputParams params) external payable returns (uint256 amountOut);
 
     struct ExactOutputParams {
@@ -25,6 +28,11 @@ sol! {
     function exactOutput(ExactOutputParams params) external payable returns (uint256 amountIn);
 }
 
+/// Offset of `params.amountIn` in the `exactInput` call data, past the params offset word.
+const EXACT_INPUT_AMOUNT_IN_OFFSET: u64 = 4 + 32 * 4;
+/// Offset of `params.deadline` in the `exactInput` call data, past the params offset word.
+const EXACT_INPUT_DEADLINE_OFFSET: u64 = 4 + 32 * 3;
+
 impl ExecutorEncoder {
     /// Swaps using UniswapV3 `exactInput`.
     pub fn uni_v3_exact_input(
@@ -51,6 +59,34 @@ impl ExecutorEncoder {
         self.push_call(router, U256::ZERO, call_data, None, vec![])
     }
 
+    /// Swaps using UniswapV3 `exactInput`, with a deadline filled with the block
+    /// timestamp at execution time, read from `multicall` (usually
+    /// [`MULTICALL3`](crate::protocols::multicall3::MULTICALL3)).
+    pub fn uni_v3_exact_input_now(
+        &mut self,
+        router: Address,
+        path: Bytes,
+        amount_in: U256,
+        amount_out_minimum: U256,
+        multicall: Address,
+        recipient: Option<Address>,
+    ) -> &mut Self {
+        let recipient = recipient.unwrap_or(self.address());
+        let placeholder = self.multicall3_block_timestamp(multicall, EXACT_INPUT_DEADLINE_OFFSET);
+        let call_data = exactInputCall {
+            params: ExactInputParams {
+                path,
+                recipient,
+                deadline: U256::ZERO,
+                amountIn: amount_in,
+                amountOutMinimum: amount_out_minimum,
+            },
+        }
+        .abi_encode()
+        .into();
+        self.push_call(router, U256::ZERO, call_data, None, vec![placeholder])
+    }
+
     /// Swaps using UniswapV3 `exactInput` with the entire balance of the input token.
     ///
     /// Uses a placeholder to dynamically read the balance at execution time.
@@ -67,11 +103,29 @@ impl ExecutorEncoder {
         deadline: U256,
         recipient: Option<Address>,
     ) -> &mut Self {
-        assert!(path.len() >= 20, "path must be at least 20 bytes");
+        self.try_uni_v3_exact_input_all(router, path, amount_out_minimum, deadline, recipient)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Fallible version of [`uni_v3_exact_input_all`](Self::uni_v3_exact_input_all).
+    ///
+    /// Returns [`Error::PathTooShort`] if `path` is shorter than 20 bytes.
+    pub fn try_uni_v3_exact_input_all(
+        &mut self,
+        router: Address,
+        path: Bytes,
+        amount_out_minimum: U256,
+        deadline: U256,
+        recipient: Option<Address>,
+    ) -> Result<&mut Self, Error> {
+        if path.len() < 20 {
+            return Err(Error::PathTooShort { len: path.len() });
+        }
         let recipient = recipient.unwrap_or(self.address());
         // Extract input token from the first 20 bytes of the path
         let input_token = Address::from_slice(&path[..20]);
-        let placeholder = self.erc20_balance_of(input_token, self.address(), 4 + 32 * 4);
+        let placeholder =
+            self.erc20_balance_of(input_token, self.address(), EXACT_INPUT_AMOUNT_IN_OFFSET);
         let call_data = exactInputCall {
             params: ExactInputParams {
                 path,
@@ -83,7 +137,7 @@ impl ExecutorEncoder {
         }
         .abi_encode()
         .into();
-        self.push_call(router, U256::ZERO, call_data, None, vec![placeholder])
+        Ok(self.push_call(router, U256::ZERO, call_data, None, vec![placeholder]))
     }
 
     /// Swaps using UniswapV3 `exactOutput`.
@@ -111,3 +165,186 @@ impl ExecutorEncoder {
         self.push_call(router, U256::ZERO, call_data, None, vec![])
     }
 }
+
+/// Returns the first and last tokens of a UniswapV3 `path`, if it holds at least one.
+fn path_ends(path: &[u8]) -> Option<(Address, Address)> {
+    (path.len() >= 20).then(|| {
+        (
+            Address::from_slice(&path[..20]),
+            Address::from_slice(&path[path.len() - 20..]),
+        )
+    })
+}
+
+/// Swaps `amount_in` along `path`, see [`ExecutorEncoder::uni_v3_exact_input`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct UniV3ExactInput {
+    pub router: Address,
+    pub path: Bytes,
+    pub amount_in: U256,
+    pub amount_out_minimum: U256,
+    pub deadline: U256,
+    pub recipient: Option<Address>,
+}
+
+impl Action for UniV3ExactInput {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.uni_v3_exact_input(
+            self.router,
+            self.path.clone(),
+            self.amount_in,
+            self.amount_out_minimum,
+            self.deadline,
+            self.recipient,
+        );
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        path_ends(&self.path)
+            .map(|(input, _)| input)
+            .into_iter()
+            .collect()
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        path_ends(&self.path)
+            .map(|(_, output)| output)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Swaps the whole balance of the input token along `path`, see
+/// [`ExecutorEncoder::uni_v3_exact_input_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct UniV3ExactInputAll {
+    pub router: Address,
+    pub path: Bytes,
+    pub amount_out_minimum: U256,
+    pub deadline: U256,
+    pub recipient: Option<Address>,
+}
+
+impl Action for UniV3ExactInputAll {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.try_uni_v3_exact_input_all(
+            self.router,
+            self.path.clone(),
+            self.amount_out_minimum,
+            self.deadline,
+            self.recipient,
+        )?;
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        path_ends(&self.path)
+            .map(|(input, _)| input)
+            .into_iter()
+            .collect()
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        path_ends(&self.path)
+            .map(|(_, output)| output)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Swaps for `amount_out` along the reversed `path`, see
+/// [`ExecutorEncoder::uni_v3_exact_output`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct UniV3ExactOutput {
+    pub router: Address,
+    pub path: Bytes,
+    pub amount_out: U256,
+    pub amount_in_maximum: U256,
+    pub deadline: U256,
+    pub recipient: Option<Address>,
+}
+
+impl Action for UniV3ExactOutput {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.uni_v3_exact_output(
+            self.router,
+            self.path.clone(),
+            self.amount_out,
+            self.amount_in_maximum,
+            self.deadline,
+            self.recipient,
+        );
+        Ok(())
+    }
+
+    // `exactOutput` paths start with the output token.
+    fn consumes(&self) -> Vec<Address> {
+        path_ends(&self.path)
+            .map(|(_, input)| input)
+            .into_iter()
+            .collect()
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        path_ends(&self.path)
+            .map(|(output, _)| output)
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::sol_arg_offset;
+
+    #[test]
+    fn test_exact_input_amount_in_offset() {
+        let call = exactInputCall {
+            params: ExactInputParams {
+                path: Bytes::new(),
+                recipient: Address::ZERO,
+                deadline: U256::ZERO,
+                amountIn: U256::ZERO,
+                amountOutMinimum: U256::ZERO,
+            },
+        };
+        assert_eq!(
+            sol_arg_offset(&call, "params.amountIn").unwrap(),
+            EXACT_INPUT_AMOUNT_IN_OFFSET
+        );
+    }
+
+    #[test]
+    fn test_exact_input_deadline_offset() {
+        let call = exactInputCall {
+            params: ExactInputParams {
+                path: Bytes::new(),
+                recipient: Address::ZERO,
+                deadline: U256::ZERO,
+                amountIn: U256::ZERO,
+                amountOutMinimum: U256::ZERO,
+            },
+        };
+        assert_eq!(
+            sol_arg_offset(&call, "params.deadline").unwrap(),
+            EXACT_INPUT_DEADLINE_OFFSET
+        );
+    }
+}
@@ -1,17 +1,25 @@
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, B256, U256};
 use alloy_sol_types::SolCall;
 
+use crate::action::Action;
 use crate::encoder::ExecutorEncoder;
+use crate::error::Error;
 
-mod weth_sol {
+pub(crate) mod weth_sol {
     use alloy_sol_types::sol;
 
     sol! {
         function deposit() external payable;
+        #[sol(abi)]
         function withdraw(uint256 wad) external;
     }
 }
 
+/// Offset of the `value` argument in the executor's `call_g0oyU7o` call data.
+const CALL_VALUE_OFFSET: u64 = 4 + 32;
+/// Offset of the `wad` argument in the `withdraw` call data.
+const WITHDRAW_WAD_OFFSET: u64 = 4;
+
 impl ExecutorEncoder {
     /// Wraps ETH into WETH by calling `deposit()` with `amount` as msg.value.
     pub fn wrap_eth(&mut self, weth: Address, amount: U256) -> &mut Self {
@@ -24,4 +32,149 @@ impl ExecutorEncoder {
         let call_data = weth_sol::withdrawCall { wad: amount }.abi_encode().into();
         self.push_call(weth, U256::ZERO, call_data, None, vec![])
     }
+
+    /// Wraps the executor's whole ETH balance into WETH, read at execution time from
+    /// `multicall` (usually [`MULTICALL3`](crate::protocols::multicall3::MULTICALL3)).
+    ///
+    /// Placeholders fill call data but not call values, so the executor calls itself
+    /// with a `call_g0oyU7o(weth, value, ..)` whose `value` argument is filled. Both the
+    /// outer self-call and the deposit leave the callback context at zero: neither
+    /// expects a callback.
+    pub fn wrap_all_eth(&mut self, weth: Address, multicall: Address) -> &mut Self {
+        let placeholder = self.multicall3_eth_balance(multicall, self.address(), CALL_VALUE_OFFSET);
+        let deposit = crate::executor_sol::call_g0oyU7oCall {
+            target: weth,
+            value: U256::ZERO,
+            context: B256::ZERO,
+            callData: weth_sol::depositCall {}.abi_encode().into(),
+        }
+        .abi_encode()
+        .into();
+        self.push_call(self.address(), U256::ZERO, deposit, None, vec![placeholder])
+    }
+
+    /// Unwraps the executor's whole WETH balance into ETH.
+    ///
+    /// Uses a placeholder to dynamically read the balance at execution time.
+    pub fn unwrap_all_eth(&mut self, weth: Address) -> &mut Self {
+        let placeholder = self.erc20_balance_of(weth, self.address(), WITHDRAW_WAD_OFFSET);
+        let call_data = weth_sol::withdrawCall { wad: U256::ZERO }
+            .abi_encode()
+            .into();
+        self.push_call(weth, U256::ZERO, call_data, None, vec![placeholder])
+    }
+}
+
+/// Wraps `amount` of ETH, see [`ExecutorEncoder::wrap_eth`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct WrapEth {
+    pub weth: Address,
+    pub amount: U256,
+}
+
+impl Action for WrapEth {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.wrap_eth(self.weth, self.amount);
+        Ok(())
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.weth]
+    }
+}
+
+/// Unwraps `amount` of WETH, see [`ExecutorEncoder::unwrap_eth`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct UnwrapEth {
+    pub weth: Address,
+    pub amount: U256,
+}
+
+impl Action for UnwrapEth {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.unwrap_eth(self.weth, self.amount);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.weth]
+    }
+}
+
+/// Wraps the executor's whole ETH balance, see [`ExecutorEncoder::wrap_all_eth`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct WrapAllEth {
+    pub weth: Address,
+    pub multicall: Address,
+}
+
+impl Action for WrapAllEth {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.wrap_all_eth(self.weth, self.multicall);
+        Ok(())
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        vec![self.weth]
+    }
+}
+
+/// Unwraps the executor's whole WETH balance, see [`ExecutorEncoder::unwrap_all_eth`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "strategy", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "strategy",
+    serde(rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct UnwrapAllEth {
+    pub weth: Address,
+}
+
+impl Action for UnwrapAllEth {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.unwrap_all_eth(self.weth);
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.weth]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::sol_arg_offset;
+
+    #[test]
+    fn test_call_value_offset() {
+        let call = crate::executor_sol::call_g0oyU7oCall {
+            target: Address::ZERO,
+            value: U256::ZERO,
+            context: B256::ZERO,
+            callData: weth_sol::depositCall {}.abi_encode().into(),
+        };
+        assert_eq!(sol_arg_offset(&call, "value").unwrap(), CALL_VALUE_OFFSET);
+    }
+
+    #[test]
+    fn test_withdraw_wad_offset() {
+        let call = weth_sol::withdrawCall { wad: U256::ZERO };
+        assert_eq!(sol_arg_offset(&call, "wad").unwrap(), WITHDRAW_WAD_OFFSET);
+    }
 }
@@ -0,0 +1,105 @@
+use alloy_sol_types::{SolCall, SolType};
+
+use crate::protocols::aave::aave_sol;
+use crate::protocols::compound::compound_sol;
+use crate::protocols::erc20::erc20_sol;
+use crate::protocols::erc20_wrapper::erc20_wrapper_sol;
+use crate::protocols::erc4626::erc4626_sol;
+use crate::protocols::flashloans::{
+    aave_pool_sol, balancer_sol, maker_sol, morpho_blue_flash_sol, uni_flash_sol,
+};
+use crate::protocols::morpho::morpho_sol;
+use crate::protocols::morpho_blue::morpho_blue_sol;
+use crate::protocols::multicall3::multicall3_sol;
+use crate::protocols::uniswap_v2::uniswap_v2_sol;
+use crate::protocols::uniswap_v3;
+use crate::protocols::weth::weth_sol;
+
+/// A function signature known to the crate, used to resolve selectors.
+pub(crate) struct KnownFunction {
+    pub(crate) selector: [u8; 4],
+    pub(crate) signature: &'static str,
+    /// Encoded size of the return data, if static.
+    pub(crate) return_size: Option<usize>,
+}
+
+const fn known<C: SolCall>() -> KnownFunction {
+    KnownFunction {
+        selector: C::SELECTOR,
+        signature: C::SIGNATURE,
+        return_size: <C::ReturnTuple<'static> as SolType>::ENCODED_SIZE,
+    }
+}
+
+/// Every function declared by the crate's `sol!` interfaces.
+///
+/// Functions sharing a selector also share a signature, but not necessarily return types
+/// (e.g. the executor's `transfer` returns nothing while ERC20's returns a `bool`).
+const KNOWN_FUNCTIONS: &[KnownFunction] = &[
+    known::<crate::executor_sol::transferCall>(),
+    known::<erc20_sol::balanceOfCall>(),
+    known::<erc20_sol::allowanceCall>(),
+    known::<erc20_sol::approveCall>(),
+    known::<erc20_sol::transferCall>(),
+    known::<erc20_sol::transferFromCall>(),
+    known::<weth_sol::depositCall>(),
+    known::<weth_sol::withdrawCall>(),
+    known::<erc4626_sol::depositCall>(),
+    known::<erc4626_sol::mintCall>(),
+    known::<erc4626_sol::withdrawCall>(),
+    known::<erc4626_sol::redeemCall>(),
+    known::<erc4626_sol::previewRedeemCall>(),
+    known::<erc4626_sol::maxWithdrawCall>(),
+    known::<erc20_wrapper_sol::depositForCall>(),
+    known::<erc20_wrapper_sol::withdrawToCall>(),
+    known::<aave_sol::depositCall>(),
+    known::<aave_sol::borrowCall>(),
+    known::<aave_sol::repayCall>(),
+    known::<aave_sol::withdrawCall>(),
+    known::<aave_sol::liquidationCallCall>(),
+    known::<aave_sol::getUserAccountDataCall>(),
+    known::<compound_sol::mintCall>(),
+    known::<compound_sol::borrowCall>(),
+    known::<compound_sol::repayBorrowCall>(),
+    known::<compound_sol::repayBorrowBehalfCall>(),
+    known::<compound_sol::redeemUnderlyingCall>(),
+    known::<morpho_sol::liquidateCall>(),
+    known::<morpho_blue_sol::supplyCollateralCall>(),
+    known::<morpho_blue_sol::withdrawCollateralCall>(),
+    known::<morpho_blue_sol::supplyCall>(),
+    known::<morpho_blue_sol::withdrawCall>(),
+    known::<morpho_blue_sol::repayCall>(),
+    known::<morpho_blue_sol::borrowCall>(),
+    known::<morpho_blue_sol::liquidateCall>(),
+    known::<morpho_blue_sol::positionCall>(),
+    known::<uniswap_v2_sol::getReservesCall>(),
+    known::<uniswap_v3::exactInputCall>(),
+    known::<uniswap_v3::exactOutputCall>(),
+    known::<balancer_sol::flashLoanCall>(),
+    known::<maker_sol::flashLoanCall>(),
+    known::<aave_pool_sol::flashLoanCall>(),
+    known::<uni_flash_sol::flashCall>(),
+    known::<morpho_blue_flash_sol::flashLoanCall>(),
+    known::<multicall3_sol::getCurrentBlockTimestampCall>(),
+    known::<multicall3_sol::getBasefeeCall>(),
+    known::<multicall3_sol::getEthBalanceCall>(),
+    known::<multicall3_sol::getBlockNumberCall>(),
+    known::<multicall3_sol::getCurrentBlockCoinbaseCall>(),
+];
+
+/// Returns the first known function matching `selector`.
+pub(crate) fn find_function(selector: &[u8]) -> Option<&'static KnownFunction> {
+    KNOWN_FUNCTIONS.iter().find(|f| f.selector == selector)
+}
+
+/// Returns the largest static return size among known functions matching `selector`.
+///
+/// `None` if the selector is unknown or any match has a dynamic return type.
+pub(crate) fn max_return_size(selector: &[u8]) -> Option<usize> {
+    let mut matches = KNOWN_FUNCTIONS
+        .iter()
+        .filter(|f| f.selector == selector)
+        .peekable();
+    matches.peek()?;
+    matches.try_fold(0, |max, f| f.return_size.map(|size| size.max(max)))
+}
@@ -0,0 +1,386 @@
+//! Declarative strategy files compiled into an [`EncodedExec`].
+//!
+//! A strategy lists actions by the name of the [`ExecutorEncoder`] method they call,
+//! with that method's parameters as named fields, in camelCase like every serialized
+//! type of the crate. Flash loans and Morpho Blue callbacks take a nested `callback`
+//! list of actions.
+//!
+//! # Example
+//! ```toml
+//! executor = "0x1111111111111111111111111111111111111111"
+//!
+//! [[actions]]
+//! action = "blue_flash_loan"
+//! morphoBlue = "0xBBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb"
+//! asset = "0x6B175474E89094C44Da98b954EedeAC495271d0F"
+//! amount = "1000000000000000000000"
+//!
+//! [[actions.callback]]
+//! action = "erc20_approve"
+//! asset = "0x6B175474E89094C44Da98b954EedeAC495271d0F"
+//! spender = "0xBBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb"
+//! allowance = "1000000000000000000000"
+//! ```
+
+use alloy_primitives::{Address, Bytes, U256};
+use serde::{Deserialize, Deserializer};
+
+use crate::action::{encode_actions, Action};
+use crate::encoder::{EncodedExec, ExecutorEncoder};
+use crate::error::Error;
+use crate::protocols::aave::{AaveBorrow, AaveLiquidate, AaveRepay, AaveSupply, AaveWithdraw};
+use crate::protocols::erc20::{
+    Erc20Approve, Erc20ApproveAll, Erc20Skim, Erc20Transfer, Erc20TransferFrom,
+};
+use crate::protocols::erc4626::{
+    Erc4626Deposit, Erc4626DepositAll, Erc4626Mint, Erc4626Redeem, Erc4626RedeemAll,
+    Erc4626Withdraw,
+};
+use crate::protocols::flashloans::{
+    AaveFlashLoan, BalancerFlashLoan, BlueFlashLoan, MakerFlashLoan, UniV2FlashSwap, UniV3FlashLoan,
+};
+use crate::protocols::morpho_blue::{
+    MorphoBlueBorrow, MorphoBlueLiquidate, MorphoBlueRepay, MorphoBlueSupply,
+    MorphoBlueSupplyCollateral, MorphoBlueWithdraw, MorphoBlueWithdrawCollateral,
+};
+use crate::protocols::uniswap_v3::{UniV3ExactInput, UniV3ExactInputAll, UniV3ExactOutput};
+use crate::protocols::weth::{UnwrapAllEth, UnwrapEth, WrapAllEth, WrapEth};
+
+/// A batch of actions to be executed by the executor at `executor`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Strategy {
+    pub executor: Address,
+    /// ETH sent on top of the value spent by the actions.
+    #[serde(default)]
+    pub extra_value: U256,
+    pub actions: Vec<StrategyAction>,
+}
+
+impl Strategy {
+    /// Parses a TOML strategy.
+    pub fn from_toml(source: &str) -> Result<Self, Error> {
+        toml::from_str(source).map_err(|err| Error::InvalidStrategy(err.to_string()))
+    }
+
+    /// Parses a JSON strategy.
+    pub fn from_json(source: &str) -> Result<Self, Error> {
+        serde_json::from_str(source).map_err(|err| Error::InvalidStrategy(err.to_string()))
+    }
+
+    /// Compiles the strategy into the `exec_606BaXt(bytes[])` transaction.
+    pub fn compile(&self) -> Result<EncodedExec, Error> {
+        let mut encoder = ExecutorEncoder::new(self.executor);
+        encode_actions(&mut encoder, &self.actions)?;
+        Ok(encoder.encode_exec(self.extra_value))
+    }
+}
+
+/// A single action, named after the [`ExecutorEncoder`] method it calls.
+///
+/// Actions with an [`Action`] type deserialize into it; the others call their method
+/// directly.
+#[derive(Debug, Deserialize)]
+#[serde(
+    tag = "action",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase",
+    deny_unknown_fields
+)]
+pub enum StrategyAction {
+    /// Raw call, see [`ExecutorEncoder::push_call`].
+    Call {
+        target: Address,
+        #[serde(default)]
+        value: U256,
+        data: Bytes,
+    },
+    Transfer {
+        recipient: Address,
+        amount: U256,
+    },
+    Tip {
+        amount: U256,
+    },
+    WrapEth(WrapEth),
+    UnwrapEth(UnwrapEth),
+    WrapAllEth(WrapAllEth),
+    UnwrapAllEth(UnwrapAllEth),
+    Erc20Approve(Erc20Approve),
+    Erc20ApproveAll(Erc20ApproveAll),
+    Erc20Transfer(Erc20Transfer),
+    Erc20TransferFrom(Erc20TransferFrom),
+    Erc20Skim(Erc20Skim),
+    Erc20WrapperDepositFor {
+        asset: Address,
+        on_behalf: Address,
+        amount: U256,
+    },
+    Erc20WrapperDepositAllFor {
+        asset: Address,
+        underlying: Address,
+        on_behalf: Address,
+    },
+    Erc20WrapperWithdrawTo {
+        asset: Address,
+        receiver: Address,
+        amount: U256,
+    },
+    Erc20WrapperWithdrawAllTo {
+        asset: Address,
+        receiver: Address,
+    },
+    Erc4626Deposit(Erc4626Deposit),
+    Erc4626DepositAll(Erc4626DepositAll),
+    Erc4626Mint(Erc4626Mint),
+    Erc4626Withdraw(Erc4626Withdraw),
+    Erc4626Redeem(Erc4626Redeem),
+    Erc4626RedeemAll(Erc4626RedeemAll),
+    AaveSupply(AaveSupply),
+    AaveBorrow(AaveBorrow),
+    AaveRepay(AaveRepay),
+    AaveWithdraw(AaveWithdraw),
+    AaveLiquidate(AaveLiquidate),
+    CompoundSupply {
+        c_token: Address,
+        amount: U256,
+    },
+    CompoundBorrow {
+        c_token: Address,
+        amount: U256,
+    },
+    CompoundRepay {
+        c_token: Address,
+        amount: U256,
+        on_behalf_of: Option<Address>,
+    },
+    CompoundWithdraw {
+        c_token: Address,
+        amount: U256,
+    },
+    MorphoCompoundLiquidate {
+        morpho_compound: Address,
+        borrowed_pool_token: Address,
+        collateral_pool_token: Address,
+        borrower: Address,
+        amount: U256,
+    },
+    MorphoAaveV2Liquidate {
+        morpho_aave_v2: Address,
+        borrowed_pool_token: Address,
+        collateral_pool_token: Address,
+        borrower: Address,
+        amount: U256,
+    },
+    MorphoAaveV3Liquidate {
+        morpho_aave_v3: Address,
+        underlying_borrowed: Address,
+        underlying_collateral: Address,
+        borrower: Address,
+        amount: U256,
+    },
+    MorphoBlueSupplyCollateral(MorphoBlueSupplyCollateral),
+    MorphoBlueWithdrawCollateral(MorphoBlueWithdrawCollateral),
+    MorphoBlueSupply(MorphoBlueSupply),
+    MorphoBlueWithdraw(MorphoBlueWithdraw),
+    MorphoBlueRepay(MorphoBlueRepay),
+    MorphoBlueBorrow(MorphoBlueBorrow),
+    MorphoBlueLiquidate(MorphoBlueLiquidate),
+    UniV3ExactInput(UniV3ExactInput),
+    UniV3ExactInputAll(UniV3ExactInputAll),
+    UniV3ExactOutput(UniV3ExactOutput),
+    BalancerFlashLoan(BalancerFlashLoan),
+    MakerFlashLoan(MakerFlashLoan),
+    AaveFlashLoan(AaveFlashLoan),
+    UniV2FlashSwap(UniV2FlashSwap),
+    UniV3FlashLoan(UniV3FlashLoan),
+    BlueFlashLoan(BlueFlashLoan),
+}
+
+impl StrategyAction {
+    /// Returns the protocol action this action deserialized into, if any.
+    fn action(&self) -> Option<&dyn Action> {
+        Some(match self {
+            Self::WrapEth(action) => action,
+            Self::UnwrapEth(action) => action,
+            Self::WrapAllEth(action) => action,
+            Self::UnwrapAllEth(action) => action,
+            Self::Erc20Approve(action) => action,
+            Self::Erc20ApproveAll(action) => action,
+            Self::Erc20Transfer(action) => action,
+            Self::Erc20TransferFrom(action) => action,
+            Self::Erc20Skim(action) => action,
+            Self::Erc4626Deposit(action) => action,
+            Self::Erc4626DepositAll(action) => action,
+            Self::Erc4626Mint(action) => action,
+            Self::Erc4626Withdraw(action) => action,
+            Self::Erc4626Redeem(action) => action,
+            Self::Erc4626RedeemAll(action) => action,
+            Self::AaveSupply(action) => action,
+            Self::AaveBorrow(action) => action,
+            Self::AaveRepay(action) => action,
+            Self::AaveWithdraw(action) => action,
+            Self::AaveLiquidate(action) => action,
+            Self::MorphoBlueSupplyCollateral(action) => action,
+            Self::MorphoBlueWithdrawCollateral(action) => action,
+            Self::MorphoBlueSupply(action) => action,
+            Self::MorphoBlueWithdraw(action) => action,
+            Self::MorphoBlueRepay(action) => action,
+            Self::MorphoBlueBorrow(action) => action,
+            Self::MorphoBlueLiquidate(action) => action,
+            Self::UniV3ExactInput(action) => action,
+            Self::UniV3ExactInputAll(action) => action,
+            Self::UniV3ExactOutput(action) => action,
+            Self::BalancerFlashLoan(action) => action,
+            Self::MakerFlashLoan(action) => action,
+            Self::AaveFlashLoan(action) => action,
+            Self::UniV2FlashSwap(action) => action,
+            Self::UniV3FlashLoan(action) => action,
+            Self::BlueFlashLoan(action) => action,
+            Self::Call { .. }
+            | Self::Transfer { .. }
+            | Self::Tip { .. }
+            | Self::Erc20WrapperDepositFor { .. }
+            | Self::Erc20WrapperDepositAllFor { .. }
+            | Self::Erc20WrapperWithdrawTo { .. }
+            | Self::Erc20WrapperWithdrawAllTo { .. }
+            | Self::CompoundSupply { .. }
+            | Self::CompoundBorrow { .. }
+            | Self::CompoundRepay { .. }
+            | Self::CompoundWithdraw { .. }
+            | Self::MorphoCompoundLiquidate { .. }
+            | Self::MorphoAaveV2Liquidate { .. }
+            | Self::MorphoAaveV3Liquidate { .. } => return None,
+        })
+    }
+}
+
+impl Action for StrategyAction {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        if let Some(action) = self.action() {
+            return action.encode(encoder);
+        }
+        match self {
+            Self::Call {
+                target,
+                value,
+                data,
+            } => {
+                encoder.try_push_call(*target, *value, data.clone(), None, vec![])?;
+            }
+            Self::Transfer { recipient, amount } => {
+                encoder.try_transfer(*recipient, *amount)?;
+            }
+            Self::Tip { amount } => {
+                encoder.tip(*amount);
+            }
+            Self::Erc20WrapperDepositFor {
+                asset,
+                on_behalf,
+                amount,
+            } => {
+                encoder.erc20_wrapper_deposit_for(*asset, *on_behalf, *amount);
+            }
+            Self::Erc20WrapperDepositAllFor {
+                asset,
+                underlying,
+                on_behalf,
+            } => {
+                encoder.erc20_wrapper_deposit_all_for(*asset, *underlying, *on_behalf);
+            }
+            Self::Erc20WrapperWithdrawTo {
+                asset,
+                receiver,
+                amount,
+            } => {
+                encoder.erc20_wrapper_withdraw_to(*asset, *receiver, *amount);
+            }
+            Self::Erc20WrapperWithdrawAllTo { asset, receiver } => {
+                encoder.erc20_wrapper_withdraw_all_to(*asset, *receiver);
+            }
+            Self::CompoundSupply { c_token, amount } => {
+                encoder.compound_supply(*c_token, *amount);
+            }
+            Self::CompoundBorrow { c_token, amount } => {
+                encoder.compound_borrow(*c_token, *amount);
+            }
+            Self::CompoundRepay {
+                c_token,
+                amount,
+                on_behalf_of,
+            } => {
+                encoder.compound_repay(*c_token, *amount, *on_behalf_of);
+            }
+            Self::CompoundWithdraw { c_token, amount } => {
+                encoder.compound_withdraw(*c_token, *amount);
+            }
+            Self::MorphoCompoundLiquidate {
+                morpho_compound,
+                borrowed_pool_token,
+                collateral_pool_token,
+                borrower,
+                amount,
+            } => {
+                encoder.morpho_compound_liquidate(
+                    *morpho_compound,
+                    *borrowed_pool_token,
+                    *collateral_pool_token,
+                    *borrower,
+                    *amount,
+                );
+            }
+            Self::MorphoAaveV2Liquidate {
+                morpho_aave_v2,
+                borrowed_pool_token,
+                collateral_pool_token,
+                borrower,
+                amount,
+            } => {
+                encoder.morpho_aave_v2_liquidate(
+                    *morpho_aave_v2,
+                    *borrowed_pool_token,
+                    *collateral_pool_token,
+                    *borrower,
+                    *amount,
+                );
+            }
+            Self::MorphoAaveV3Liquidate {
+                morpho_aave_v3,
+                underlying_borrowed,
+                underlying_collateral,
+                borrower,
+                amount,
+            } => {
+                encoder.morpho_aave_v3_liquidate(
+                    *morpho_aave_v3,
+                    *underlying_borrowed,
+                    *underlying_collateral,
+                    *borrower,
+                    *amount,
+                );
+            }
+            // Encoded by their protocol action, above.
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        self.action().map_or_else(Vec::new, Action::consumes)
+    }
+
+    fn produces(&self) -> Vec<Address> {
+        self.action().map_or_else(Vec::new, Action::produces)
+    }
+
+    fn callback(&self) -> &[Box<dyn Action>] {
+        self.action().map_or(&[], Action::callback)
+    }
+}
+
+/// Deserializes a [`StrategyAction`], e.g. the `callback` actions of a flash loan.
+impl<'de> Deserialize<'de> for Box<dyn Action> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        StrategyAction::deserialize(deserializer).map(|action| Box::new(action) as _)
+    }
+}
@@ -1,17 +1,36 @@
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_sol_types::SolValue;
+
+use crate::callbacks::callback_data_index;
+use crate::error::Error;
 
 /// Context for callback-based calls.
 ///
 /// `sender` is the address expected to call back.
 /// `data_index` is the callback data parameter index in the callback function signature.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct CallbackContext {
     pub sender: Address,
     pub data_index: u64,
 }
 
+impl CallbackContext {
+    /// Returns the context of a call that `sender` calls back with the known callback
+    /// `name`, or with the full signature `name` (see [`callback_data_index`]).
+    pub fn for_callback(sender: Address, name: &str) -> Result<Self, Error> {
+        Ok(Self {
+            sender,
+            data_index: callback_data_index(name)?,
+        })
+    }
+}
+
 /// A request for a specific amount of an asset (used in flash loans).
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct AssetRequest {
     pub asset: Address,
     pub amount: U256,
@@ -19,6 +38,8 @@ pub struct AssetRequest {
 
 /// Morpho Blue market parameters.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct MarketParams {
     pub loan_token: Address,
     pub collateral_token: Address,
@@ -26,3 +47,19 @@ pub struct MarketParams {
     pub irm: Address,
     pub lltv: U256,
 }
+
+impl MarketParams {
+    /// Returns the Morpho Blue market id, `keccak256(abi.encode(marketParams))`.
+    pub fn id(&self) -> B256 {
+        keccak256(
+            (
+                self.loan_token,
+                self.collateral_token,
+                self.oracle,
+                self.irm,
+                self.lltv,
+            )
+                .abi_encode(),
+        )
+    }
+}
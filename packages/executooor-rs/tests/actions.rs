@@ -0,0 +1,224 @@
+use alloy_primitives::{address, Address, Bytes, U256};
+use executooor::action::{encode_actions, Action};
+use executooor::encoder::ExecutorEncoder;
+use executooor::error::Error;
+use executooor::protocols::aave::AaveLiquidate;
+use executooor::protocols::erc20::{Erc20Approve, Erc20Skim, Erc20Transfer, Erc20TransferFrom};
+use executooor::protocols::erc4626::{Erc4626DepositAll, Erc4626RedeemAll};
+use executooor::protocols::flashloans::BlueFlashLoan;
+use executooor::protocols::uniswap_v3::UniV3ExactInputAll;
+use executooor::protocols::weth::{UnwrapAllEth, WrapEth};
+
+const DAI: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+const SDAI: Address = address!("83F20F44975D03b1b09e64809B757c47f942BEeA");
+const MORPHO_BLUE: Address = address!("BBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb");
+const AAVE_POOL: Address = address!("87870Bca3F3fD6335C3F4ce8392D69350B4fA4E2");
+const ROUTER: Address = address!("E592427A0AEce92De3Edee1F18E0157C05861564");
+const EXECUTOR: Address = address!("1111111111111111111111111111111111111111");
+const USER: Address = address!("2222222222222222222222222222222222222222");
+
+fn dai_to_weth_path() -> Bytes {
+    let mut path = DAI.to_vec();
+    path.extend_from_slice(&500u32.to_be_bytes()[1..]);
+    path.extend_from_slice(WETH.as_slice());
+    path.into()
+}
+
+fn flash_plan(amount: U256) -> Vec<Box<dyn Action>> {
+    vec![Box::new(BlueFlashLoan {
+        morpho_blue: MORPHO_BLUE,
+        asset: DAI,
+        amount,
+        callback: vec![
+            Box::new(Erc4626DepositAll {
+                vault: SDAI,
+                asset: DAI,
+                owner: EXECUTOR,
+            }),
+            Box::new(Erc4626RedeemAll {
+                vault: SDAI,
+                asset: DAI,
+                receiver: EXECUTOR,
+                owner: EXECUTOR,
+            }),
+        ],
+    })]
+}
+
+// ============================================================
+// 1. actions encode like the encoder methods they wrap
+// ============================================================
+#[test]
+fn test_actions_match_methods() {
+    let amount = U256::from(1_000_000u64);
+
+    let mut from_actions = ExecutorEncoder::new(EXECUTOR);
+    encode_actions(&mut from_actions, &flash_plan(amount)).unwrap();
+
+    let mut from_methods = ExecutorEncoder::new(EXECUTOR);
+    from_methods.blue_flash_loan_with(MORPHO_BLUE, DAI, amount, |encoder| {
+        encoder
+            .erc4626_deposit_all(SDAI, DAI, EXECUTOR)
+            .erc4626_redeem_all(SDAI, EXECUTOR, EXECUTOR);
+    });
+
+    assert_eq!(from_actions.calls(), from_methods.calls());
+    assert_eq!(
+        from_actions.encode_exec(U256::ZERO).data,
+        from_methods.encode_exec(U256::ZERO).data
+    );
+}
+
+#[test]
+fn test_token_actions_match_methods() {
+    let amount = U256::from(1_000_000u64);
+    let plan: Vec<Box<dyn Action>> = vec![
+        Box::new(WrapEth { weth: WETH, amount }),
+        Box::new(Erc20Approve {
+            asset: WETH,
+            spender: ROUTER,
+            allowance: amount,
+        }),
+        Box::new(Erc20TransferFrom {
+            asset: DAI,
+            owner: USER,
+            recipient: EXECUTOR,
+            amount,
+        }),
+        Box::new(Erc20Transfer {
+            asset: DAI,
+            recipient: USER,
+            amount,
+        }),
+        Box::new(UnwrapAllEth { weth: WETH }),
+        Box::new(Erc20Skim {
+            asset: DAI,
+            recipient: USER,
+        }),
+    ];
+    let mut from_actions = ExecutorEncoder::new(EXECUTOR);
+    encode_actions(&mut from_actions, &plan).unwrap();
+
+    let mut from_methods = ExecutorEncoder::new(EXECUTOR);
+    from_methods
+        .wrap_eth(WETH, amount)
+        .erc20_approve(WETH, ROUTER, amount)
+        .erc20_transfer_from(DAI, USER, EXECUTOR, amount)
+        .erc20_transfer(DAI, USER, amount)
+        .unwrap_all_eth(WETH)
+        .erc20_skim(DAI, USER);
+
+    assert_eq!(from_actions.calls(), from_methods.calls());
+    assert_eq!(from_actions.value(), amount);
+
+    assert_eq!(plan[0].produces(), vec![WETH]);
+    assert!(plan[1].consumes().is_empty());
+    assert_eq!(plan[2].produces(), vec![DAI]);
+    assert_eq!(plan[3].consumes(), vec![DAI]);
+    assert_eq!(plan[4].consumes(), vec![WETH]);
+}
+
+// ============================================================
+// 2. plans can be inspected through their actions
+// ============================================================
+#[test]
+fn test_action_tokens() {
+    let plan: Vec<Box<dyn Action>> = vec![
+        Box::new(AaveLiquidate {
+            pool: AAVE_POOL,
+            collateral: WETH,
+            debt: DAI,
+            user: USER,
+            amount: U256::MAX,
+        }),
+        Box::new(UniV3ExactInputAll {
+            router: ROUTER,
+            path: dai_to_weth_path(),
+            amount_out_minimum: U256::ZERO,
+            deadline: U256::MAX,
+            recipient: None,
+        }),
+    ];
+    assert_eq!(plan[0].consumes(), vec![DAI]);
+    assert_eq!(plan[0].produces(), vec![WETH]);
+    assert_eq!(plan[1].consumes(), vec![DAI]);
+    assert_eq!(plan[1].produces(), vec![WETH]);
+    assert!(plan[1].callback().is_empty());
+
+    let flash = flash_plan(U256::from(1u64));
+    assert_eq!(flash[0].consumes(), vec![DAI]);
+    assert_eq!(flash[0].produces(), vec![DAI]);
+    let callback = flash[0].callback();
+    assert_eq!(callback.len(), 2);
+    assert_eq!(callback[0].consumes(), vec![DAI]);
+    assert_eq!(callback[0].produces(), vec![SDAI]);
+    assert_eq!(callback[1].consumes(), vec![SDAI]);
+    assert_eq!(callback[1].produces(), vec![DAI]);
+}
+
+// ============================================================
+// 3. downstream actions and failing actions
+// ============================================================
+#[derive(Debug)]
+struct Skim {
+    token: Address,
+}
+
+impl Action for Skim {
+    fn encode(&self, encoder: &mut ExecutorEncoder) -> Result<(), Error> {
+        encoder.erc20_transfer(self.token, USER, U256::from(1u64));
+        Ok(())
+    }
+
+    fn consumes(&self) -> Vec<Address> {
+        vec![self.token]
+    }
+}
+
+#[test]
+fn test_custom_action() {
+    let plan: Vec<Box<dyn Action>> = vec![Box::new(BlueFlashLoan {
+        morpho_blue: MORPHO_BLUE,
+        asset: DAI,
+        amount: U256::from(10u64),
+        callback: vec![Box::new(Skim { token: DAI })],
+    })];
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encode_actions(&mut encoder, &plan).unwrap();
+
+    let mut expected = ExecutorEncoder::new(EXECUTOR);
+    expected.blue_flash_loan_with(MORPHO_BLUE, DAI, U256::from(10u64), |encoder| {
+        encoder.erc20_transfer(DAI, USER, U256::from(1u64));
+    });
+    assert_eq!(encoder.calls(), expected.calls());
+}
+
+#[test]
+fn test_failing_action_pushes_nothing() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    let err = encode_actions(&mut encoder, &flash_plan(U256::ZERO)).unwrap_err();
+    assert!(matches!(err, Error::EmptyFlashLoan));
+    assert!(encoder.calls().is_empty());
+    assert_eq!(encoder.value(), U256::ZERO);
+
+    let plan: Vec<Box<dyn Action>> = vec![Box::new(UniV3ExactInputAll {
+        router: ROUTER,
+        path: Bytes::from(vec![0u8; 3]),
+        amount_out_minimum: U256::ZERO,
+        deadline: U256::MAX,
+        recipient: None,
+    })];
+    let err = encode_actions(&mut encoder, &plan).unwrap_err();
+    assert!(matches!(err, Error::PathTooShort { len: 3 }));
+    assert!(encoder.calls().is_empty());
+
+    // The callback encoded before the failing flash loan isn't left pending either.
+    let amount = U256::from(1u64);
+    encode_actions(&mut encoder, &flash_plan(amount)).unwrap();
+    let mut expected = ExecutorEncoder::new(EXECUTOR);
+    encode_actions(&mut expected, &flash_plan(amount)).unwrap();
+    assert_eq!(encoder.calls(), expected.calls());
+    assert_eq!(encoder.metadata(), expected.metadata());
+    assert_eq!(encoder.value(), expected.value());
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "cli")]
+
+use std::process::Command;
+
+use alloy_primitives::{address, hex, Address, U256};
+use executooor::decoder::{decode_exec, DecodedCall};
+use executooor::deploy::EXECUTOR_BYTECODE;
+use executooor::encoder::{EncodedExec, ExecutorEncoder};
+
+const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+const BALANCER_VAULT: Address = address!("BA12222222228d8Ba445958a75a0704d566BF2C8");
+const EXECUTOR: Address = address!("1111111111111111111111111111111111111111");
+
+fn executooor(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_executooor"))
+        .args(args)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    (output.status.success(), stdout.trim_end().to_string())
+}
+
+// ============================================================
+// 1. encode from an argument list
+// ============================================================
+#[test]
+fn test_cli_encode_actions() {
+    let (ok, stdout) = executooor(&[
+        "encode",
+        "--executor",
+        &EXECUTOR.to_string(),
+        "--action",
+        &format!("wrap_eth weth={WETH} amount=1000"),
+    ]);
+    assert!(ok);
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(1000u64));
+    let exec = encoder.encode_exec(U256::ZERO);
+    assert_eq!(stdout, serde_json::to_string_pretty(&exec).unwrap());
+}
+
+// ============================================================
+// 2. explain
+// ============================================================
+#[test]
+fn test_cli_explain() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(1000u64));
+    let exec = encoder.encode_exec(U256::ZERO);
+
+    let (ok, stdout) = executooor(&[
+        "explain",
+        &exec.data.to_string(),
+        "--label",
+        &format!("{WETH}=WETH"),
+    ]);
+    assert!(ok);
+    assert_eq!(stdout, "WETH.deposit() {value: 1000}");
+
+    let (ok, _) = executooor(&["explain", "0xdeadbeef"]);
+    assert!(!ok);
+}
+
+// ============================================================
+// 3. context
+// ============================================================
+#[test]
+fn test_cli_context() {
+    let (ok, context) = executooor(&["context", "encode", &BALANCER_VAULT.to_string(), "3"]);
+    assert!(ok);
+    assert_eq!(
+        context,
+        "0x000000000000000000000003ba12222222228d8ba445958a75a0704d566bf2c8"
+    );
+
+    let (ok, decoded) = executooor(&["context", "decode", &context]);
+    assert!(ok);
+    assert_eq!(decoded, format!("sender: {BALANCER_VAULT}\ndata index: 3"));
+}
+
+// ============================================================
+// 4. decode an encoded strategy
+// ============================================================
+#[test]
+fn test_cli_decode_round_trip() {
+    let (ok, encoded) = executooor(&[
+        "encode",
+        "--executor",
+        &EXECUTOR.to_string(),
+        "--action",
+        &format!("wrap_eth weth={WETH} amount=1000"),
+        "--action",
+        &format!("unwrap_eth weth={WETH} amount=400"),
+    ]);
+    assert!(ok);
+    let exec: EncodedExec = serde_json::from_str(&encoded).unwrap();
+
+    let (ok, decoded) = executooor(&["decode", &exec.data.to_string()]);
+    assert!(ok);
+    let calls: Vec<DecodedCall> = serde_json::from_str(&decoded).unwrap();
+    assert_eq!(calls, decode_exec(&exec.data).unwrap());
+    assert_eq!(calls.len(), 2);
+    assert_eq!(decoded, serde_json::to_string_pretty(&calls).unwrap());
+}
+
+// ============================================================
+// 5. deploy-bytecode
+// ============================================================
+#[test]
+fn test_cli_deploy_bytecode() {
+    let (ok, stdout) = executooor(&["deploy-bytecode", &EXECUTOR.to_string()]);
+    assert!(ok);
+
+    let bytecode = hex::decode(&stdout).unwrap();
+    let (code, owner) = bytecode.split_at(EXECUTOR_BYTECODE.len());
+    assert_eq!(code, EXECUTOR_BYTECODE);
+    assert_eq!(owner, EXECUTOR.into_word().as_slice());
+}
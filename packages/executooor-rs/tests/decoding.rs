@@ -0,0 +1,280 @@
+use alloy_primitives::{address, Address, Bytes, U256};
+use executooor::decoder::{decode_call, decode_exec, DecodeError, ExecPlan};
+use executooor::encoder::{EncodedExec, ExecutorEncoder};
+use executooor::types::{AssetRequest, CallbackContext, MarketParams};
+
+const DAI: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+const BALANCER_VAULT: Address = address!("BA12222222228d8Ba445958a75a0704d566BF2C8");
+const AAVE_V2_POOL: Address = address!("7d2768dE32b0b80b7a3454c06BdAc94A69DDc7A9");
+const EXECUTOR: Address = address!("1111111111111111111111111111111111111111");
+
+// ============================================================
+// 1. decode_exec — flat batch
+// ============================================================
+#[test]
+fn test_decode_exec_flat() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .erc20_approve(DAI, AAVE_V2_POOL, U256::from(500u64))
+        .wrap_eth(WETH, U256::from(1000u64));
+    let EncodedExec { data, .. } = encoder.encode_exec(U256::ZERO);
+
+    let calls = decode_exec(&data).unwrap();
+    assert_eq!(calls.len(), 2);
+
+    assert_eq!(calls[0].target, DAI);
+    assert_eq!(calls[0].value, U256::ZERO);
+    assert_eq!(calls[0].context, CallbackContext::default());
+    assert!(calls[0].placeholders.is_empty());
+    assert!(calls[0].callback.is_none());
+    assert_eq!(&calls[0].call_data[..4], &[0x09, 0x5e, 0xa7, 0xb3]);
+
+    assert_eq!(calls[1].target, WETH);
+    assert_eq!(calls[1].value, U256::from(1000u64));
+}
+
+// ============================================================
+// 2. decode_call — placeholders
+// ============================================================
+#[test]
+fn test_decode_call_placeholders() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.erc20_skim(DAI, EXECUTOR);
+    let calls = encoder.flush();
+
+    let call = decode_call(&calls[0]).unwrap();
+    assert_eq!(call.target, DAI);
+    assert_eq!(call.placeholders.len(), 1);
+    assert_eq!(
+        call.placeholders[0],
+        encoder.erc20_balance_of(DAI, EXECUTOR, 4 + 32)
+    );
+}
+
+// ============================================================
+// 3. decode_exec — flash loan callback
+// ============================================================
+#[test]
+fn test_decode_exec_callback() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.aave_supply(AAVE_V2_POOL, DAI, U256::from(100u64), None);
+    let inner = encoder.flush();
+
+    encoder.balancer_flash_loan(
+        BALANCER_VAULT,
+        &[AssetRequest {
+            asset: DAI,
+            amount: U256::from(100u64),
+        }],
+        Some(inner.clone()),
+    );
+    let EncodedExec { data, .. } = encoder.encode_exec(U256::ZERO);
+
+    let calls = decode_exec(&data).unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].target, BALANCER_VAULT);
+    assert_eq!(
+        calls[0].context,
+        CallbackContext {
+            sender: BALANCER_VAULT,
+            data_index: 3,
+        }
+    );
+
+    let callback = calls[0].callback.as_ref().unwrap();
+    assert_eq!(callback.return_value, Bytes::new());
+    assert_eq!(callback.calls.len(), 2);
+    assert_eq!(callback.calls[0], decode_call(&inner[0]).unwrap());
+    assert_eq!(
+        callback.calls[1],
+        decode_call(&ExecutorEncoder::build_erc20_transfer(
+            DAI,
+            BALANCER_VAULT,
+            U256::from(100u64)
+        ))
+        .unwrap()
+    );
+}
+
+// ============================================================
+// 4. decode_exec — nested callbacks
+// ============================================================
+#[test]
+fn test_decode_exec_nested_callbacks() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(7u64));
+    let innermost = encoder.flush();
+
+    encoder.aave_flash_loan(
+        AAVE_V2_POOL,
+        &[AssetRequest {
+            asset: WETH,
+            amount: U256::from(10u64),
+        }],
+        U256::from(5u64),
+        Some(innermost),
+    );
+    let middle = encoder.flush();
+
+    encoder.balancer_flash_loan(
+        BALANCER_VAULT,
+        &[AssetRequest {
+            asset: DAI,
+            amount: U256::from(100u64),
+        }],
+        Some(middle),
+    );
+    let EncodedExec { data, .. } = encoder.encode_exec(U256::ZERO);
+
+    let calls = decode_exec(&data).unwrap();
+    let balancer = calls[0].callback.as_ref().unwrap();
+    let aave = &balancer.calls[0];
+    assert_eq!(aave.target, AAVE_V2_POOL);
+    assert_eq!(aave.context.data_index, 4);
+
+    let aave_callback = aave.callback.as_ref().unwrap();
+    assert_eq!(aave_callback.return_value[31], 1);
+    assert_eq!(aave_callback.calls[0].target, WETH);
+    assert_eq!(aave_callback.calls[0].value, U256::from(7u64));
+    assert!(aave_callback.calls[0].callback.is_none());
+}
+
+#[test]
+fn test_decoded_total_value() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .wrap_eth(WETH, U256::from(1u64))
+        .blue_flash_loan_with(BALANCER_VAULT, DAI, U256::from(1u64), |inner| {
+            inner.wrap_eth(WETH, U256::from(2u64));
+        });
+    let EncodedExec { data, value, .. } = encoder.encode_exec(U256::ZERO);
+
+    let total: U256 = decode_exec(&data)
+        .unwrap()
+        .iter()
+        .map(|call| call.total_value())
+        .sum();
+    assert_eq!(total, U256::from(3u64));
+    assert_eq!(total, value);
+}
+
+// ============================================================
+// 5. decode_exec — callback data located for every lender
+// ============================================================
+#[test]
+fn test_decode_exec_all_lenders() {
+    let amount = U256::from(100u64);
+    let market = MarketParams {
+        loan_token: DAI,
+        collateral_token: WETH,
+        oracle: Address::ZERO,
+        irm: Address::ZERO,
+        lltv: U256::ZERO,
+    };
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .maker_flash_loan(BALANCER_VAULT, DAI, amount, None)
+        .uni_v3_flash_loan(
+            BALANCER_VAULT,
+            [DAI, WETH],
+            [amount, amount],
+            U256::from(500u64),
+            None,
+        )
+        .blue_flash_loan(BALANCER_VAULT, DAI, amount, None)
+        .morpho_blue_liquidate(BALANCER_VAULT, &market, EXECUTOR, amount, U256::ZERO, None);
+    let EncodedExec { data, .. } = encoder.encode_exec(U256::ZERO);
+
+    let calls = decode_exec(&data).unwrap();
+    let repayments: Vec<usize> = calls
+        .iter()
+        .map(|call| call.callback.as_ref().unwrap().calls.len())
+        .collect();
+    assert_eq!(repayments, vec![1, 2, 1, 0]);
+}
+
+// ============================================================
+// 6. decode errors
+// ============================================================
+#[test]
+fn test_decode_unknown_selector() {
+    let data = [0xde, 0xad, 0xbe, 0xef];
+    assert!(matches!(
+        decode_exec(&data),
+        Err(DecodeError::UnknownSelector([0xde, 0xad, 0xbe, 0xef]))
+    ));
+    assert!(matches!(
+        decode_call(&data),
+        Err(DecodeError::UnknownSelector(_))
+    ));
+}
+
+#[test]
+fn test_decode_callback_not_found() {
+    let ctx = CallbackContext {
+        sender: BALANCER_VAULT,
+        data_index: 3,
+    };
+    let call =
+        ExecutorEncoder::build_call(BALANCER_VAULT, U256::ZERO, Bytes::new(), Some(&ctx), vec![]);
+    assert!(matches!(
+        decode_call(&call),
+        Err(DecodeError::CallbackDataNotFound { data_index: 3 })
+    ));
+}
+
+// ============================================================
+// 7. structured plans
+// ============================================================
+#[test]
+fn test_exec_plan() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.blue_flash_loan_with(BALANCER_VAULT, DAI, U256::from(1u64), |inner| {
+        inner.wrap_eth(WETH, U256::from(2u64));
+    });
+    let exec = encoder.encode_exec(U256::from(3u64));
+
+    let plan = ExecPlan::decode(&exec).unwrap();
+    assert_eq!(plan.to, EXECUTOR);
+    assert_eq!(plan.value, U256::from(5u64));
+    assert_eq!(plan.calls, decode_exec(&exec.data).unwrap());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_exec_plan_serde_roundtrip() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.erc20_skim(DAI, EXECUTOR).blue_flash_loan_with(
+        BALANCER_VAULT,
+        DAI,
+        U256::from(1u64),
+        |inner| {
+            inner.wrap_eth(WETH, U256::from(2u64));
+        },
+    );
+    let exec = encoder.encode_exec(U256::ZERO);
+    let plan = ExecPlan::decode(&exec).unwrap();
+
+    let json = serde_json::to_value(&plan).unwrap();
+    assert_eq!(json["calls"][0]["target"], serde_json::json!(DAI));
+    assert_eq!(
+        json["calls"][0]["placeholders"][0]["resOffset"],
+        serde_json::json!(0)
+    );
+    assert_eq!(
+        json["calls"][1]["context"]["dataIndex"],
+        serde_json::json!(1)
+    );
+    assert_eq!(
+        json["calls"][1]["callback"]["calls"][0]["target"],
+        serde_json::json!(WETH)
+    );
+
+    assert_eq!(serde_json::from_value::<ExecPlan>(json).unwrap(), plan);
+    let exec_json = serde_json::to_string(&exec).unwrap();
+    assert_eq!(
+        serde_json::from_str::<EncodedExec>(&exec_json).unwrap(),
+        exec
+    );
+}
@@ -1,7 +1,13 @@
 use alloy_primitives::hex;
 use alloy_primitives::{address, Address, Bytes, U256};
-use executooor::encoder::{encode_callback_data, EncodedExec, ExecutorEncoder};
-use executooor::types::CallbackContext;
+use executooor::deploy::{deploy_bytecode, EXECUTOR_BYTECODE};
+use executooor::encoder::{encode_callback_data, CallMetadata, EncodedExec, ExecutorEncoder};
+use executooor::layout::sol_arg_offset;
+use executooor::protocols::aave::AaveAccountData;
+use executooor::protocols::morpho_blue::MorphoBluePosition;
+use executooor::protocols::uniswap_v2::UniV2Reserves;
+use executooor::types::{AssetRequest, CallbackContext, MarketParams};
+use executooor::Error;
 
 const DAI: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
 const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
@@ -276,3 +282,1604 @@ fn test_chaining() {
     let calls = encoder.flush();
     assert_eq!(calls.len(), 3);
 }
+
+// ============================================================
+// 18. callback closure matches manual flush
+// ============================================================
+#[test]
+fn test_flash_loan_with_closure() {
+    let requests = [AssetRequest {
+        asset: DAI,
+        amount: U256::from(100u64),
+    }];
+
+    let mut manual = ExecutorEncoder::new(EXECUTOR);
+    manual.aave_supply(AAVE_V2_POOL, DAI, U256::from(100u64), None);
+    let callback_calls = manual.flush();
+    manual.balancer_flash_loan(BALANCER_VAULT, &requests, Some(callback_calls));
+
+    let mut scoped = ExecutorEncoder::new(EXECUTOR);
+    scoped.balancer_flash_loan_with(BALANCER_VAULT, &requests, |inner| {
+        assert_eq!(inner.address(), EXECUTOR);
+        inner.aave_supply(AAVE_V2_POOL, DAI, U256::from(100u64), None);
+    });
+
+    assert_eq!(scoped.flush(), manual.flush());
+}
+
+// ============================================================
+// 19. nested callback closures
+// ============================================================
+#[test]
+fn test_nested_callback_closures() {
+    let market = MarketParams {
+        loan_token: DAI,
+        collateral_token: WETH,
+        oracle: Address::ZERO,
+        irm: Address::ZERO,
+        lltv: U256::ZERO,
+    };
+
+    let mut manual = ExecutorEncoder::new(EXECUTOR);
+    manual.erc20_approve(DAI, BALANCER_VAULT, U256::from(1u64));
+    let liquidate_calls = manual.flush();
+    manual.morpho_blue_liquidate(
+        BALANCER_VAULT,
+        &market,
+        EXECUTOR,
+        U256::from(1u64),
+        U256::ZERO,
+        Some(liquidate_calls),
+    );
+    let flash_calls = manual.flush();
+    manual.blue_flash_loan(BALANCER_VAULT, DAI, U256::from(1u64), Some(flash_calls));
+
+    let mut scoped = ExecutorEncoder::new(EXECUTOR);
+    scoped.blue_flash_loan_with(BALANCER_VAULT, DAI, U256::from(1u64), |flash| {
+        flash.morpho_blue_liquidate_with(
+            BALANCER_VAULT,
+            &market,
+            EXECUTOR,
+            U256::from(1u64),
+            U256::ZERO,
+            |liquidate| {
+                liquidate.erc20_approve(DAI, BALANCER_VAULT, U256::from(1u64));
+            },
+        );
+    });
+
+    assert_eq!(scoped.flush(), manual.flush());
+}
+
+// ============================================================
+// 20. value of flushed callback calls is carried up
+// ============================================================
+#[test]
+fn test_nested_value_after_flush() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(100u64));
+    let callback_calls = encoder.flush();
+    assert_eq!(encoder.value(), U256::from(100u64));
+
+    encoder
+        .blue_flash_loan(BALANCER_VAULT, DAI, U256::from(1u64), Some(callback_calls))
+        .wrap_eth(WETH, U256::from(20u64));
+
+    let EncodedExec { value, .. } = encoder.encode_exec(U256::from(3u64));
+    assert_eq!(value, U256::from(123u64));
+    assert_eq!(encoder.value(), U256::ZERO);
+}
+
+#[test]
+fn test_clear_drops_flushed_value() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(100u64));
+    encoder.flush();
+    encoder.wrap_eth(WETH, U256::from(20u64));
+    encoder.clear();
+    assert!(encoder.is_empty());
+    assert_eq!(encoder.value(), U256::ZERO);
+
+    encoder.wrap_eth(WETH, U256::from(3u64));
+    let EncodedExec { value, .. } = encoder.encode_exec(U256::ZERO);
+    assert_eq!(value, U256::from(3u64));
+}
+
+// ============================================================
+// 21. value of sub-encoder callback calls is carried up
+// ============================================================
+#[test]
+fn test_nested_value_with_closures() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.blue_flash_loan_with(BALANCER_VAULT, DAI, U256::from(1u64), |flash| {
+        flash
+            .wrap_eth(WETH, U256::from(10u64))
+            .blue_flash_loan_with(BALANCER_VAULT, DAI, U256::from(1u64), |inner| {
+                inner.wrap_eth(WETH, U256::from(5u64));
+            });
+    });
+
+    let EncodedExec { value, .. } = encoder.encode_exec(U256::ZERO);
+    assert_eq!(value, U256::from(15u64));
+}
+
+// ============================================================
+// 22. fallible builders return errors instead of panicking
+// ============================================================
+#[test]
+fn test_try_transfer_zero_recipient() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    assert!(matches!(
+        encoder.try_transfer(Address::ZERO, U256::from(1u64)),
+        Err(Error::ZeroRecipient)
+    ));
+    assert!(encoder.flush().is_empty());
+
+    encoder
+        .try_transfer(DAI, U256::from(500u64))
+        .unwrap()
+        .tip(U256::from(1u64));
+    assert_eq!(encoder.flush().len(), 2);
+}
+
+#[test]
+fn test_try_uni_v3_exact_input_all_short_path() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    let result = encoder.try_uni_v3_exact_input_all(
+        BALANCER_VAULT,
+        Bytes::from(vec![0u8; 19]),
+        U256::ZERO,
+        U256::MAX,
+        None,
+    );
+    assert!(matches!(result, Err(Error::PathTooShort { len: 19 })));
+}
+
+#[test]
+fn test_try_flash_loan_validation() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    let amount = U256::from(1u64);
+
+    assert!(matches!(
+        encoder.try_balancer_flash_loan(BALANCER_VAULT, &[], None),
+        Err(Error::EmptyFlashLoan)
+    ));
+    assert!(matches!(
+        encoder.try_blue_flash_loan(BALANCER_VAULT, DAI, U256::ZERO, None),
+        Err(Error::EmptyFlashLoan)
+    ));
+    assert!(matches!(
+        encoder.try_uni_v3_flash_loan(
+            BALANCER_VAULT,
+            [DAI, Address::ZERO],
+            [amount, amount],
+            U256::from(500u64),
+            None
+        ),
+        Err(Error::ZeroAsset { index: 1 })
+    ));
+    assert!(matches!(
+        encoder.try_aave_flash_loan(
+            AAVE_V2_POOL,
+            &[
+                AssetRequest { asset: DAI, amount },
+                AssetRequest { asset: DAI, amount },
+            ],
+            U256::from(5u64),
+            None
+        ),
+        Err(Error::DuplicateAsset(DAI))
+    ));
+    assert!(encoder.flush().is_empty());
+
+    encoder
+        .try_uni_v3_flash_loan(
+            BALANCER_VAULT,
+            [DAI, WETH],
+            [amount, U256::ZERO],
+            U256::from(500u64),
+            None,
+        )
+        .unwrap();
+    assert_eq!(encoder.flush().len(), 1);
+}
+
+#[test]
+fn test_flash_loan_skips_validation() {
+    // Only the `try_*` versions validate the requested assets.
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.maker_flash_loan_with(BALANCER_VAULT, DAI, U256::ZERO, |encoder| {
+        encoder.erc20_skim(DAI, EXECUTOR);
+    });
+    assert_eq!(encoder.len(), 1);
+}
+
+// ============================================================
+// 23. typed sol! calls
+// ============================================================
+mod user_sol {
+    alloy_sol_types::sol! {
+        function approve(address spender, uint256 amount) external returns (bool);
+        function deposit() external payable;
+    }
+}
+
+#[test]
+fn test_push_sol_call() {
+    let mut expected = ExecutorEncoder::new(EXECUTOR);
+    expected
+        .erc20_approve(DAI, AAVE_V2_POOL, U256::from(500u64))
+        .wrap_eth(WETH, U256::from(7u64));
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .push_sol_call(
+            DAI,
+            user_sol::approveCall {
+                spender: AAVE_V2_POOL,
+                amount: U256::from(500u64),
+            },
+        )
+        .push_sol_call_with(
+            WETH,
+            U256::from(7u64),
+            None,
+            vec![],
+            user_sol::depositCall {},
+        );
+
+    assert_eq!(
+        encoder.encode_exec(U256::ZERO),
+        expected.encode_exec(U256::ZERO)
+    );
+}
+
+// ============================================================
+// 24. placeholder offsets computed from the ABI
+// ============================================================
+mod layout_sol {
+    alloy_sol_types::sol! {
+        #[sol(abi)]
+        function approve(address spender, uint256 amount) external returns (bool);
+
+        #[sol(abi)]
+        function deposit(uint256 assets, address receiver) external returns (uint256);
+
+        struct ExactInputParams {
+            bytes path;
+            address recipient;
+            uint256 deadline;
+            uint256 amountIn;
+            uint256 amountOutMinimum;
+        }
+
+        #[sol(abi)]
+        function exactInput(ExactInputParams params) external payable returns (uint256 amountOut);
+
+        #[sol(abi)]
+        function batch(bytes data, uint256[] amounts, uint256[2] limits) external;
+    }
+}
+
+#[test]
+fn test_sol_arg_offset_matches_builtin_offsets() {
+    let approve = layout_sol::approveCall {
+        spender: AAVE_V2_POOL,
+        amount: U256::ZERO,
+    };
+    assert_eq!(sol_arg_offset(&approve, "amount").unwrap(), 4 + 32);
+    assert_eq!(sol_arg_offset(&approve, "1").unwrap(), 4 + 32);
+
+    let deposit = layout_sol::depositCall {
+        assets: U256::ZERO,
+        receiver: EXECUTOR,
+    };
+    assert_eq!(sol_arg_offset(&deposit, "assets").unwrap(), 4);
+
+    let exact_input = layout_sol::exactInputCall {
+        params: layout_sol::ExactInputParams {
+            path: Bytes::from(DAI.to_vec()),
+            recipient: EXECUTOR,
+            deadline: U256::MAX,
+            amountIn: U256::ZERO,
+            amountOutMinimum: U256::ZERO,
+        },
+    };
+    assert_eq!(
+        sol_arg_offset(&exact_input, "params.amountIn").unwrap(),
+        4 + 32 * 4
+    );
+    assert_eq!(sol_arg_offset(&exact_input, "0.3").unwrap(), 4 + 32 * 4);
+}
+
+#[test]
+fn test_balance_placeholders_fill_amount_arguments() {
+    use alloy_sol_types::SolCall;
+
+    alloy_sol_types::sol! {
+        function depositFor(address owner, uint256 amount) external;
+        function withdrawTo(address receiver, uint256 amount) external;
+    }
+
+    // Fills each placeholder with a marker and decodes the call with it.
+    fn filled(encoder: &ExecutorEncoder) -> Vec<u8> {
+        let call = executooor::decoder::decode_call(&encoder.calls()[0]).unwrap();
+        let mut data = call.call_data.to_vec();
+        let offset = call.placeholders[0].offset as usize;
+        data[offset..offset + 32].copy_from_slice(&U256::from(42u64).to_be_bytes::<32>());
+        data
+    }
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.erc20_wrapper_deposit_all_for(WETH, DAI, AAVE_V2_POOL);
+    let call = depositForCall::abi_decode(&filled(&encoder)).unwrap();
+    assert_eq!(call.owner, AAVE_V2_POOL);
+    assert_eq!(call.amount, U256::from(42u64));
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.erc20_wrapper_withdraw_all_to(WETH, AAVE_V2_POOL);
+    let call = withdrawToCall::abi_decode(&filled(&encoder)).unwrap();
+    assert_eq!(call.receiver, AAVE_V2_POOL);
+    assert_eq!(call.amount, U256::from(42u64));
+}
+
+#[test]
+fn test_sol_arg_offset_arrays_and_errors() {
+    let batch = layout_sol::batchCall {
+        data: Bytes::from(vec![0xff; 40]),
+        amounts: vec![U256::from(1u64), U256::from(2u64)],
+        limits: [U256::from(3u64), U256::from(4u64)],
+    };
+    let data = alloy_sol_types::SolCall::abi_encode(&batch);
+
+    let offset = sol_arg_offset(&batch, "amounts.1").unwrap() as usize;
+    assert_eq!(
+        U256::from_be_slice(&data[offset..offset + 32]),
+        U256::from(2u64)
+    );
+    let offset = sol_arg_offset(&batch, "limits.1").unwrap() as usize;
+    assert_eq!(
+        U256::from_be_slice(&data[offset..offset + 32]),
+        U256::from(4u64)
+    );
+
+    assert!(matches!(
+        sol_arg_offset(&batch, "data"),
+        Err(Error::DynamicArgument(_))
+    ));
+    assert!(matches!(
+        sol_arg_offset(&batch, "amounts.2"),
+        Err(Error::InvalidArgumentPath(_))
+    ));
+    assert!(matches!(
+        sol_arg_offset(&batch, "unknown"),
+        Err(Error::InvalidArgumentPath(_))
+    ));
+}
+
+#[test]
+fn test_push_sol_call_with_placeholder() {
+    let mut expected = ExecutorEncoder::new(EXECUTOR);
+    expected.erc20_approve_all(DAI, AAVE_V2_POOL);
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    let placeholder = encoder.erc20_balance_of(DAI, EXECUTOR, 0);
+    encoder
+        .push_sol_call_with_placeholder(
+            DAI,
+            layout_sol::approveCall {
+                spender: AAVE_V2_POOL,
+                amount: U256::ZERO,
+            },
+            "amount",
+            placeholder,
+        )
+        .unwrap();
+
+    assert_eq!(encoder.flush(), expected.flush());
+}
+
+// ============================================================
+// 25. typed placeholder sources
+// ============================================================
+#[test]
+fn test_placeholder_sources() {
+    let encoder = ExecutorEncoder::new(EXECUTOR);
+
+    let allowance = encoder.erc20_allowance(DAI, EXECUTOR, AAVE_V2_POOL, 36);
+    assert_eq!(allowance.to, DAI);
+    assert_eq!(&allowance.data[..4], &[0xdd, 0x62, 0xed, 0x3e]);
+    assert_eq!(
+        (allowance.offset, allowance.length, allowance.resOffset),
+        (36, 32, 0)
+    );
+
+    let preview = encoder.erc4626_preview_redeem(WETH, U256::from(1u64), 4);
+    assert_eq!(&preview.data[..4], &[0x4c, 0xda, 0xd5, 0x06]);
+
+    let max_withdraw = encoder.erc4626_max_withdraw(WETH, EXECUTOR, 4);
+    assert_eq!(&max_withdraw.data[..4], &[0xce, 0x96, 0xcb, 0x77]);
+
+    let health_factor =
+        encoder.aave_user_account_data(AAVE_V2_POOL, EXECUTOR, AaveAccountData::HealthFactor, 68);
+    assert_eq!(&health_factor.data[..4], &[0xbf, 0x92, 0x85, 0x7c]);
+    assert_eq!(health_factor.resOffset, 32 * 5);
+    assert_eq!(health_factor.offset, 68);
+
+    let reserve1 = encoder.uni_v2_reserves(WETH, UniV2Reserves::Reserve1, 4);
+    assert_eq!(reserve1.data, h("0x0902f1ac"));
+    assert_eq!(reserve1.resOffset, 32);
+}
+
+#[test]
+fn test_morpho_blue_position_source() {
+    let market = MarketParams {
+        loan_token: DAI,
+        collateral_token: WETH,
+        oracle: AAVE_V2_POOL,
+        irm: BALANCER_VAULT,
+        lltv: U256::from(860_000_000_000_000_000u64),
+    };
+    let mut encoded = Vec::new();
+    for address in [DAI, WETH, AAVE_V2_POOL, BALANCER_VAULT] {
+        encoded.extend_from_slice(address.into_word().as_slice());
+    }
+    encoded.extend_from_slice(&market.lltv.to_be_bytes::<32>());
+    assert_eq!(market.id(), alloy_primitives::keccak256(&encoded));
+
+    let encoder = ExecutorEncoder::new(EXECUTOR);
+    let collateral = encoder.morpho_blue_position(
+        BALANCER_VAULT,
+        &market,
+        EXECUTOR,
+        MorphoBluePosition::Collateral,
+        4 + 32 * 5 + 32,
+    );
+    assert_eq!(&collateral.data[..4], &[0x93, 0xc5, 0x20, 0x62]);
+    assert_eq!(&collateral.data[4..36], market.id().as_slice());
+    assert_eq!(collateral.resOffset, 64);
+}
+
+// ============================================================
+// 26. placeholder bounds are validated at encode time
+// ============================================================
+#[test]
+fn test_try_build_call_placeholder_bounds() {
+    let encoder = ExecutorEncoder::new(EXECUTOR);
+    // approve(address,uint256) call data is 68 bytes long.
+    let call_data = h("0x095ea7b30000000000000000000000007d2768de32b0b80b7a3454c06bdac94a69ddc7a90000000000000000000000000000000000000000000000000000000000000000");
+
+    let in_bounds = encoder.erc20_balance_of(DAI, EXECUTOR, 36);
+    assert!(ExecutorEncoder::try_build_call(
+        DAI,
+        U256::ZERO,
+        call_data.clone(),
+        None,
+        vec![in_bounds.clone()]
+    )
+    .is_ok());
+
+    let past_end = encoder.erc20_balance_of(DAI, EXECUTOR, 37);
+    assert!(matches!(
+        ExecutorEncoder::try_build_call(DAI, U256::ZERO, call_data.clone(), None, vec![past_end]),
+        Err(Error::PlaceholderOutOfBounds {
+            index: 0,
+            end: 69,
+            len: 68
+        })
+    ));
+
+    let overlapping = encoder.erc20_allowance(DAI, EXECUTOR, AAVE_V2_POOL, 20);
+    assert!(matches!(
+        ExecutorEncoder::try_build_call(
+            DAI,
+            U256::ZERO,
+            call_data.clone(),
+            None,
+            vec![in_bounds, overlapping]
+        ),
+        Err(Error::OverlappingPlaceholders(0, 1))
+    ));
+
+    let mut past_result = encoder.erc20_balance_of(DAI, EXECUTOR, 36);
+    past_result.resOffset = 32;
+    assert!(matches!(
+        ExecutorEncoder::try_build_call(DAI, U256::ZERO, call_data, None, vec![past_result]),
+        Err(Error::PlaceholderResultOutOfBounds {
+            index: 0,
+            end: 64,
+            return_size: 32
+        })
+    ));
+}
+
+#[test]
+fn test_try_push_call_leaves_encoder_untouched_on_error() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    let placeholder = encoder.erc20_balance_of(DAI, EXECUTOR, 4);
+    let result = encoder.try_push_call(
+        WETH,
+        U256::from(1u64),
+        h("0xd0e30db0"),
+        None,
+        vec![placeholder],
+    );
+    assert!(matches!(result, Err(Error::PlaceholderOutOfBounds { .. })));
+    assert_eq!(encoder.value(), U256::ZERO);
+    assert!(encoder.flush().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "placeholder 0 writes up to byte 36 of 4-byte call data")]
+fn test_push_call_panics_on_invalid_placeholder() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    let placeholder = encoder.erc20_balance_of(DAI, EXECUTOR, 4);
+    encoder.push_call(WETH, U256::ZERO, h("0xd0e30db0"), None, vec![placeholder]);
+}
+
+// ============================================================
+// 27. per-call labels and metadata
+// ============================================================
+#[test]
+fn test_label_and_tag_last_call() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .label("ignored")
+        .wrap_eth(WETH, U256::from(1u64))
+        .label("wrap")
+        .tag("asset", "WETH")
+        .erc20_approve(DAI, EXECUTOR, U256::from(2u64));
+
+    assert_eq!(
+        encoder.metadata(),
+        &[
+            CallMetadata {
+                label: Some("wrap".to_string()),
+                tags: vec![("asset".to_string(), "WETH".to_string())],
+                callback: vec![],
+            },
+            CallMetadata::default(),
+        ]
+    );
+
+    let (exec, metadata) = encoder.encode_exec_with_metadata(U256::ZERO);
+    assert_eq!(exec.value, U256::from(1u64));
+    assert_eq!(metadata.len(), 2);
+    assert!(encoder.metadata().is_empty());
+}
+
+#[test]
+fn test_metadata_nested_in_callbacks() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .blue_flash_loan_with(BALANCER_VAULT, DAI, U256::from(1u64), |inner| {
+            inner.wrap_eth(WETH, U256::from(2u64)).label("inner wrap");
+        })
+        .label("loan");
+
+    let metadata = encoder.metadata();
+    assert_eq!(metadata.len(), 1);
+    assert_eq!(metadata[0].label.as_deref(), Some("loan"));
+    assert_eq!(metadata[0].callback.len(), 1);
+    assert_eq!(metadata[0].callback[0].label.as_deref(), Some("inner wrap"));
+}
+
+#[test]
+fn test_encode_exec_clears_metadata() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(1u64)).label("pending");
+    encoder.flush();
+    encoder.encode_exec(U256::ZERO);
+
+    encoder.blue_flash_loan(BALANCER_VAULT, DAI, U256::from(1u64), None);
+    assert!(encoder.metadata()[0].callback.is_empty());
+}
+
+// ============================================================
+// 28. editable call list
+// ============================================================
+#[test]
+fn test_insert_remove_move_calls() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .wrap_eth(WETH, U256::from(1u64))
+        .label("wrap")
+        .erc20_transfer(DAI, EXECUTOR, U256::from(2u64));
+    let transfer = encoder.calls()[1].clone();
+    assert_eq!(encoder.len(), 2);
+
+    encoder.insert_call(0, WETH, U256::from(4u64), h("0xd0e30db0"), None, vec![]);
+    assert_eq!(encoder.len(), 3);
+    assert_eq!(encoder.value(), U256::from(5u64));
+    assert_eq!(encoder.metadata()[1].label.as_deref(), Some("wrap"));
+
+    encoder.move_call(2, 0).unwrap();
+    assert_eq!(encoder.calls()[0], transfer);
+    assert_eq!(encoder.metadata()[2].label.as_deref(), Some("wrap"));
+
+    encoder.swap_calls(1, 2).unwrap();
+    assert_eq!(encoder.metadata()[1].label.as_deref(), Some("wrap"));
+
+    encoder.remove_call(1).unwrap();
+    assert_eq!(encoder.value(), U256::from(4u64));
+    assert!(encoder.metadata().iter().all(|m| m.label.is_none()));
+
+    let EncodedExec { value, .. } = encoder.encode_exec(U256::ZERO);
+    assert_eq!(value, U256::from(4u64));
+    assert!(encoder.is_empty());
+}
+
+#[test]
+fn test_remove_callback_call_drops_nested_calls() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .blue_flash_loan_with(BALANCER_VAULT, DAI, U256::from(100u64), |inner| {
+            inner.wrap_eth(WETH, U256::from(4u64)).label("wrap");
+        })
+        .wrap_eth(WETH, U256::from(1u64));
+    assert_eq!(encoder.value(), U256::from(5u64));
+
+    encoder.move_call(0, 1).unwrap();
+    encoder.swap_calls(0, 1).unwrap();
+    encoder.move_call(0, 1).unwrap();
+    assert_eq!(
+        encoder.metadata()[1].callback[0].label.as_deref(),
+        Some("wrap")
+    );
+    assert_eq!(encoder.value(), U256::from(5u64));
+
+    encoder.remove_call(1).unwrap();
+    assert_eq!(encoder.value(), U256::from(1u64));
+    assert!(encoder.metadata()[0].callback.is_empty());
+
+    // The removed callback is gone for good: the next callback call nests nothing.
+    encoder.blue_flash_loan(BALANCER_VAULT, DAI, U256::from(100u64), None);
+    assert!(encoder.metadata()[1].callback.is_empty());
+    let EncodedExec { value, .. } = encoder.encode_exec(U256::ZERO);
+    assert_eq!(value, U256::from(1u64));
+}
+
+#[test]
+fn test_call_index_out_of_bounds() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(1u64));
+
+    assert!(matches!(
+        encoder.remove_call(1),
+        Err(Error::CallIndexOutOfBounds { index: 1, len: 1 })
+    ));
+    assert!(matches!(
+        encoder.move_call(0, 1),
+        Err(Error::CallIndexOutOfBounds { index: 1, len: 1 })
+    ));
+    assert!(matches!(
+        encoder.try_insert_call(2, WETH, U256::ZERO, h("0xd0e30db0"), None, vec![]),
+        Err(Error::CallIndexOutOfBounds { index: 2, len: 1 })
+    ));
+    assert_eq!(encoder.len(), 1);
+}
+
+#[test]
+fn test_checkpoint_rollback() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(1u64));
+    let checkpoint = encoder.checkpoint();
+    let before = encoder.calls().to_vec();
+
+    encoder
+        .wrap_eth(WETH, U256::from(2u64))
+        .blue_flash_loan_with(BALANCER_VAULT, DAI, U256::from(1u64), |inner| {
+            inner.wrap_eth(WETH, U256::from(4u64));
+        });
+    encoder.remove_call(0).unwrap();
+    encoder.flush();
+    assert_eq!(encoder.value(), U256::from(6u64));
+
+    encoder.rollback(checkpoint);
+    assert_eq!(encoder.calls(), &before[..]);
+    assert_eq!(encoder.value(), U256::from(1u64));
+    assert_eq!(encoder.metadata().len(), 1);
+}
+
+// ============================================================
+// 29. composing encoders
+// ============================================================
+#[test]
+fn test_append_encoder() {
+    let mut swap = ExecutorEncoder::new(EXECUTOR);
+    swap.wrap_eth(WETH, U256::from(2u64)).label("swap");
+    let flushed = swap.flush();
+    swap.wrap_eth(WETH, U256::from(4u64));
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(1u64));
+    encoder.append(&mut swap);
+
+    assert!(swap.is_empty());
+    assert_eq!(swap.value(), U256::ZERO);
+    assert_eq!(encoder.len(), 2);
+    assert_eq!(encoder.value(), U256::from(7u64));
+
+    // Metadata flushed by `swap` is attached to the call nesting its calls.
+    encoder.blue_flash_loan(BALANCER_VAULT, DAI, U256::from(1u64), Some(flushed));
+    assert_eq!(
+        encoder.metadata()[2].callback[0].label.as_deref(),
+        Some("swap")
+    );
+    assert_eq!(encoder.value(), U256::from(7u64));
+}
+
+#[test]
+fn test_flushed_calls_skip_calls_without_callback() {
+    let market = MarketParams {
+        loan_token: DAI,
+        collateral_token: WETH,
+        oracle: Address::ZERO,
+        irm: Address::ZERO,
+        lltv: U256::ZERO,
+    };
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(2u64)).label("wrap");
+    let flushed = encoder.flush();
+
+    // Morpho Blue always sets a callback context, even without callback calls.
+    encoder.morpho_blue_supply(
+        BALANCER_VAULT,
+        &market,
+        U256::from(1u64),
+        U256::ZERO,
+        EXECUTOR,
+        None,
+    );
+    encoder.blue_flash_loan(BALANCER_VAULT, DAI, U256::from(1u64), None);
+    assert!(encoder.metadata().iter().all(|m| m.callback.is_empty()));
+
+    encoder.remove_call(0).unwrap();
+    encoder.blue_flash_loan(BALANCER_VAULT, DAI, U256::from(1u64), Some(flushed));
+    assert_eq!(
+        encoder.metadata()[1].callback[0].label.as_deref(),
+        Some("wrap")
+    );
+    encoder.remove_call(1).unwrap();
+    assert_eq!(encoder.value(), U256::ZERO);
+}
+
+#[test]
+fn test_nest_callback_encoder() {
+    let mut liquidation = ExecutorEncoder::new(EXECUTOR);
+    liquidation
+        .wrap_eth(WETH, U256::from(3u64))
+        .label("liquidate");
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    let callback = encoder.nest_callback(&mut liquidation);
+    encoder
+        .blue_flash_loan(BALANCER_VAULT, DAI, U256::from(1u64), Some(callback))
+        .label("loan");
+
+    assert!(liquidation.is_empty());
+    assert_eq!(liquidation.value(), U256::ZERO);
+    assert_eq!(encoder.value(), U256::from(3u64));
+    assert_eq!(
+        encoder.metadata()[0].callback[0].label.as_deref(),
+        Some("liquidate")
+    );
+}
+
+#[test]
+fn test_merge_different_executors() {
+    const OTHER: Address = address!("2222222222222222222222222222222222222222");
+    let mut other = ExecutorEncoder::new(OTHER);
+    other.wrap_eth(WETH, U256::from(1u64));
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    assert!(matches!(
+        encoder.try_append(&mut other),
+        Err(Error::ExecutorMismatch {
+            expected: EXECUTOR,
+            found: OTHER
+        })
+    ));
+    assert!(matches!(
+        encoder.try_nest_callback(&mut other),
+        Err(Error::ExecutorMismatch { .. })
+    ));
+    assert_eq!(other.len(), 1);
+    assert!(encoder.is_empty());
+}
+
+// ============================================================
+// 30. deployment bytecode
+// ============================================================
+#[test]
+fn test_deploy_bytecode() {
+    let bytecode = deploy_bytecode(EXECUTOR);
+    assert_eq!(&bytecode[..EXECUTOR_BYTECODE.len()], EXECUTOR_BYTECODE);
+    assert_eq!(bytecode.len(), EXECUTOR_BYTECODE.len() + 32);
+    assert_eq!(&bytecode[bytecode.len() - 20..], EXECUTOR.as_slice());
+}
+
+// ============================================================
+// 31. direct encoding matches alloy's generic encoder
+// ============================================================
+#[test]
+fn test_direct_encoding_matches_alloy() {
+    use alloy_sol_types::{SolCall, SolValue};
+    use executooor::{
+        callWithPlaceholders4845164670Call, call_g0oyU7oCall, encode_context, exec_606BaXtCall,
+    };
+
+    let ctx = CallbackContext {
+        sender: BALANCER_VAULT,
+        data_index: 3,
+    };
+    let call_data = h("0xa9059cbb00000000000000000000000011111111111111111111111111111111111111110000000000000000000000000000000000000000000000000000000000000001ff");
+    let encoder = ExecutorEncoder::new(EXECUTOR);
+    let placeholders = vec![
+        encoder.erc20_balance_of(DAI, EXECUTOR, 36),
+        encoder.erc20_allowance(WETH, EXECUTOR, DAI, 4),
+    ];
+
+    assert_eq!(
+        ExecutorEncoder::build_call(DAI, U256::from(7u64), call_data.clone(), Some(&ctx), vec![]),
+        Bytes::from(
+            call_g0oyU7oCall {
+                target: DAI,
+                value: U256::from(7u64),
+                context: encode_context(ctx.sender, ctx.data_index),
+                callData: call_data.clone(),
+            }
+            .abi_encode()
+        )
+    );
+    let with_placeholders = ExecutorEncoder::build_call(
+        DAI,
+        U256::ZERO,
+        call_data.clone(),
+        None,
+        placeholders.clone(),
+    );
+    assert_eq!(
+        with_placeholders,
+        Bytes::from(
+            callWithPlaceholders4845164670Call {
+                target: DAI,
+                value: U256::ZERO,
+                context: Default::default(),
+                callData: call_data.clone(),
+                placeholders,
+            }
+            .abi_encode()
+        )
+    );
+
+    let calls = vec![call_data.clone(), Bytes::new(), with_placeholders];
+    assert_eq!(
+        encode_callback_data(calls.clone(), h("0x01")),
+        Bytes::from((calls.clone(), h("0x01")).abi_encode_params())
+    );
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    for call in &calls {
+        encoder.push_call(DAI, U256::ZERO, call.clone(), None, vec![]);
+    }
+    let expected: Vec<Bytes> = encoder.calls().to_vec();
+    assert_eq!(
+        encoder.encode_exec(U256::ZERO).data,
+        Bytes::from(exec_606BaXtCall { data: expected }.abi_encode())
+    );
+}
+
+#[test]
+fn test_callback_calls_written_in_place() {
+    // Each lender embeds the callback payload as its last dynamic argument: decoding
+    // the executor call and re-encoding its callback must give back the same payload.
+    let inner = {
+        let mut encoder = ExecutorEncoder::new(EXECUTOR);
+        encoder
+            .wrap_eth(WETH, U256::from(1u64))
+            .erc20_skim(DAI, EXECUTOR);
+        encoder.flush()
+    };
+    let amount = U256::from(100u64);
+    let requests = [AssetRequest { asset: DAI, amount }];
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .balancer_flash_loan(BALANCER_VAULT, &requests, Some(inner.clone()))
+        .aave_flash_loan(
+            AAVE_V2_POOL,
+            &requests,
+            U256::from(5u64),
+            Some(inner.clone()),
+        )
+        .maker_flash_loan(BALANCER_VAULT, DAI, amount, Some(inner.clone()))
+        .blue_flash_loan(BALANCER_VAULT, DAI, amount, Some(inner.clone()));
+    let exec = encoder.encode_exec(U256::ZERO);
+
+    for call in executooor::decode_exec(&exec.data).unwrap() {
+        let callback = call.callback.unwrap();
+        assert_eq!(callback.calls.len(), inner.len() + 1);
+        for (decoded, expected) in callback.calls.iter().zip(&inner) {
+            assert_eq!(
+                *decoded,
+                executooor::decoder::decode_call(expected).unwrap()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_push_callback_call_requires_trailing_empty_bytes() {
+    alloy_sol_types::sol! {
+        function ping() external;
+        function loan(bytes data, uint256[] ids) external;
+        function borrow(uint256 amount, bytes data) external;
+    }
+
+    let ctx = CallbackContext {
+        sender: BALANCER_VAULT,
+        data_index: 1,
+    };
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    assert!(matches!(
+        encoder.try_push_callback_call(DAI, U256::ZERO, pingCall {}, &ctx, vec![], Bytes::new()),
+        Err(Error::InvalidCallbackCall(signature)) if signature == "ping()"
+    ));
+    let call = loanCall {
+        data: Bytes::new(),
+        ids: vec![U256::from(1u64)],
+    };
+    assert!(matches!(
+        encoder.try_push_callback_call(DAI, U256::ZERO, call, &ctx, vec![], Bytes::new()),
+        Err(Error::InvalidCallbackCall(signature)) if signature == "loan(bytes,uint256[])"
+    ));
+    let call = borrowCall {
+        amount: U256::from(1u64),
+        data: h("0x01"),
+    };
+    assert!(matches!(
+        encoder.try_push_callback_call(DAI, U256::ZERO, call, &ctx, vec![], Bytes::new()),
+        Err(Error::InvalidCallbackCall(_))
+    ));
+    assert!(encoder.is_empty());
+
+    let call = borrowCall {
+        amount: U256::from(1u64),
+        data: Bytes::new(),
+    };
+    let inner = ExecutorEncoder::build_erc20_approve(DAI, AAVE_V2_POOL, U256::from(1u64));
+    encoder
+        .try_push_callback_call(DAI, U256::ZERO, call, &ctx, vec![inner], Bytes::new())
+        .unwrap();
+    let decoded = executooor::decoder::decode_call(&encoder.calls()[0]).unwrap();
+    assert_eq!(decoded.callback.unwrap().calls.len(), 1);
+}
+
+// ============================================================
+// 32. dynamic calls from JSON ABIs
+// ============================================================
+const VAULT_ABI: &str = r#"[
+    {"type":"function","name":"deposit","stateMutability":"nonpayable","outputs":[{"name":"shares","type":"uint256"}],
+     "inputs":[{"name":"assets","type":"uint256"},{"name":"receiver","type":"address"}]},
+    {"type":"function","name":"withdraw","stateMutability":"nonpayable","outputs":[],
+     "inputs":[{"name":"assets","type":"uint256"}]},
+    {"type":"function","name":"withdraw","stateMutability":"nonpayable","outputs":[],
+     "inputs":[{"name":"assets","type":"uint256"},{"name":"receiver","type":"address"}]},
+    {"type":"function","name":"maxDeposit","stateMutability":"view","outputs":[{"name":"","type":"uint256"}],
+     "inputs":[{"name":"receiver","type":"address"}]}
+]"#;
+
+mod vault_sol {
+    alloy_sol_types::sol! {
+        function deposit(uint256 assets, address receiver) external returns (uint256 shares);
+    }
+}
+
+#[test]
+fn test_dyn_call_matches_sol_call() {
+    use alloy_sol_types::SolCall;
+    use executooor::dynamic::{encode_dyn_call, find_function, parse_abi};
+
+    let abi = parse_abi(VAULT_ABI).unwrap();
+    let deposit = find_function(&abi, "deposit").unwrap();
+    let call_data = encode_dyn_call(deposit, &["1.5ether", &EXECUTOR.to_string()]).unwrap();
+    let expected = vault_sol::depositCall {
+        assets: U256::from(1_500_000_000_000_000_000u64),
+        receiver: EXECUTOR,
+    }
+    .abi_encode();
+    assert_eq!(call_data, expected);
+
+    // Overloads are picked by full signature.
+    assert!(matches!(
+        find_function(&abi, "withdraw"),
+        Err(Error::UnknownFunction(_))
+    ));
+    let withdraw = find_function(&abi, "withdraw(uint256,address)").unwrap();
+    assert_eq!(withdraw.inputs.len(), 2);
+    assert!(find_function(&abi, "withdraw(address)").is_err());
+
+    assert!(matches!(
+        encode_dyn_call(deposit, &["1"]),
+        Err(Error::ArgumentCount {
+            expected: 2,
+            found: 1
+        })
+    ));
+    match encode_dyn_call(deposit, &["1", "not an address"]) {
+        Err(Error::InvalidArgument { name, .. }) => assert_eq!(name, "receiver"),
+        other => panic!("expected an invalid argument, got {other:?}"),
+    }
+    assert!(matches!(parse_abi("{}"), Err(Error::InvalidAbi(_))));
+}
+
+#[test]
+fn test_push_dyn_call_with_placeholders() {
+    use executooor::dynamic::{encode_dyn_call, find_function, parse_abi};
+
+    let vault = address!("83F20F44975D03b1b09e64809B757c47f942BEeA");
+    let abi = parse_abi(VAULT_ABI).unwrap();
+    let deposit = find_function(&abi, "deposit").unwrap();
+    let receiver = EXECUTOR.to_string();
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    let balance = encoder.erc20_balance_of(DAI, EXECUTOR, 0);
+    let max_deposit = encoder
+        .dyn_view_placeholder(
+            vault,
+            find_function(&abi, "maxDeposit").unwrap(),
+            &[&receiver],
+            0,
+        )
+        .unwrap();
+    assert_eq!(
+        max_deposit.data,
+        encode_dyn_call(find_function(&abi, "maxDeposit").unwrap(), &[&receiver]).unwrap()
+    );
+
+    encoder
+        .push_dyn_call(
+            vault,
+            U256::ZERO,
+            deposit,
+            &["0", &receiver],
+            vec![("assets", balance.clone())],
+        )
+        .unwrap();
+    let mut expected_placeholder = balance.clone();
+    expected_placeholder.offset = 4;
+    assert_eq!(
+        encoder.calls()[0],
+        ExecutorEncoder::build_call(
+            vault,
+            U256::ZERO,
+            encode_dyn_call(deposit, &["0", &receiver]).unwrap(),
+            None,
+            vec![expected_placeholder],
+        )
+    );
+
+    // Arguments missing from the ABI cannot be filled by placeholders.
+    assert!(matches!(
+        encoder.push_dyn_call(
+            vault,
+            U256::ZERO,
+            deposit,
+            &["0", &receiver],
+            vec![("shares", balance)],
+        ),
+        Err(Error::InvalidArgumentPath(_))
+    ));
+    assert_eq!(encoder.len(), 1);
+}
+
+// ============================================================
+// 33. callback registry
+// ============================================================
+#[test]
+fn test_callback_registry() {
+    use executooor::callback_data_index;
+    use executooor::callbacks::{data_index, find_callback, KNOWN_CALLBACKS};
+
+    let expected = [
+        ("receiveFlashLoan", 3),
+        ("onFlashLoan", 4),
+        ("executeOperation", 4),
+        ("uniswapV2Call", 3),
+        ("uniswapV3FlashCallback", 2),
+        ("uniswapV3SwapCallback", 2),
+        ("onMorphoFlashLoan", 1),
+        ("onMorphoSupply", 1),
+        ("onMorphoSupplyCollateral", 1),
+        ("onMorphoRepay", 1),
+        ("onMorphoLiquidate", 1),
+    ];
+    assert_eq!(KNOWN_CALLBACKS.len(), expected.len());
+    for (name, index) in expected {
+        assert_eq!(callback_data_index(name).unwrap(), index, "{name}");
+        let callback = find_callback(name).unwrap();
+        assert_eq!(callback.name(), name);
+        assert_eq!(find_callback(callback.signature), Some(callback));
+        assert_eq!(
+            callback.selector,
+            alloy_primitives::keccak256(callback.signature)[..4]
+        );
+    }
+    assert_eq!(
+        find_callback("onFlashLoan").unwrap().signature,
+        "onFlashLoan(address,address,uint256,uint256,bytes)"
+    );
+
+    // Unknown callbacks, by signature.
+    assert_eq!(data_index("onLoan(address,uint256,bytes)"), Some(2));
+    assert_eq!(
+        data_index("onLoan((uint256,bytes) loan, uint256[2] fees, bytes calldata data)"),
+        Some(3)
+    );
+    assert_eq!(data_index("onLoan(bytes32,bytes[],bytes)"), Some(2));
+    assert_eq!(
+        data_index("onLoan((uint256,address),uint256,bytes)"),
+        Some(3)
+    );
+    assert_eq!(
+        data_index("onLoan(uint256[2][3] amounts, bytes data)"),
+        Some(6)
+    );
+    assert_eq!(data_index("onLoan((uint256,bytes)[2],bytes)"), Some(1));
+    assert_eq!(data_index("onLoan(bytes)"), Some(0));
+    assert_eq!(data_index("onLoan(Loan loan, bytes data)"), None);
+    assert_eq!(data_index("onLoan(uint256[x],bytes)"), None);
+    assert_eq!(data_index("onLoan(uint256)"), None);
+    assert_eq!(data_index("onLoan(bytes,bytes)"), None);
+    assert_eq!(data_index("onLoan(bytes"), None);
+    assert_eq!(data_index("onLoan(bytes)x"), None);
+    assert!(matches!(
+        callback_data_index("onLoan"),
+        Err(Error::UnknownCallback(name)) if name == "onLoan"
+    ));
+    assert!(matches!(
+        callback_data_index("onLoan(uint256)"),
+        Err(Error::InvalidCallbackSignature(_))
+    ));
+
+    let ctx = CallbackContext::for_callback(DAI, "executeOperation").unwrap();
+    assert_eq!(
+        ctx,
+        CallbackContext {
+            sender: DAI,
+            data_index: 4
+        }
+    );
+}
+
+#[test]
+fn test_flash_loans_use_callback_registry() {
+    use executooor::callbacks::{EXECUTE_OPERATION, RECEIVE_FLASH_LOAN};
+    use executooor::decoder::decode_call;
+
+    let requests = [AssetRequest {
+        asset: DAI,
+        amount: U256::from(1000u64),
+    }];
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .balancer_flash_loan(BALANCER_VAULT, &requests, None)
+        .aave_flash_loan(AAVE_V2_POOL, &requests, U256::from(5u64), None);
+
+    let balancer = decode_call(&encoder.calls()[0]).unwrap();
+    assert_eq!(balancer.context, RECEIVE_FLASH_LOAN.context(BALANCER_VAULT));
+    let aave = decode_call(&encoder.calls()[1]).unwrap();
+    assert_eq!(aave.context, EXECUTE_OPERATION.context(AAVE_V2_POOL));
+}
+
+// ============================================================
+// 34. generic callback calls
+// ============================================================
+#[test]
+fn test_callback_call_reproduces_flash_loans() {
+    use executooor::callbacks::{ON_FLASH_LOAN, UNISWAP_V3_FLASH_CALLBACK};
+    use executooor::protocols::flashloans::{Fee, Repayment};
+
+    alloy_sol_types::sol! {
+        function flashLoan(address receiver, address token, uint256 amount, bytes data) external;
+        function flash(address receiver, uint256 amount0, uint256 amount1, bytes data) external;
+    }
+
+    let amount = U256::from(1_000_000u64);
+    let mut expected = ExecutorEncoder::new(EXECUTOR);
+    expected
+        .maker_flash_loan_with(BALANCER_VAULT, DAI, amount, |inner| {
+            inner.erc20_transfer(DAI, EXECUTOR, U256::from(1u64));
+        })
+        .uni_v3_flash_loan(
+            AAVE_V2_POOL,
+            [DAI, WETH],
+            [amount, U256::ZERO],
+            U256::from(500u64),
+            None,
+        );
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    let magic =
+        Bytes::from(alloy_primitives::keccak256("ERC3156FlashBorrower.onFlashLoan").to_vec());
+    encoder.callback_call_with(
+        BALANCER_VAULT,
+        flashLoanCall {
+            receiver: EXECUTOR,
+            token: DAI,
+            amount,
+            data: Bytes::new(),
+        },
+        &ON_FLASH_LOAN.context(BALANCER_VAULT),
+        magic,
+        &Repayment::approve(BALANCER_VAULT, vec![AssetRequest { asset: DAI, amount }]),
+        |inner| {
+            inner.erc20_transfer(DAI, EXECUTOR, U256::from(1u64));
+        },
+    );
+    let loans = vec![
+        AssetRequest { asset: DAI, amount },
+        AssetRequest {
+            asset: WETH,
+            amount: U256::ZERO,
+        },
+    ];
+    encoder.callback_call(
+        AAVE_V2_POOL,
+        flashCall {
+            receiver: EXECUTOR,
+            amount0: amount,
+            amount1: U256::ZERO,
+            data: Bytes::new(),
+        },
+        &UNISWAP_V3_FLASH_CALLBACK.context(AAVE_V2_POOL),
+        Bytes::new(),
+        &Repayment::transfer(AAVE_V2_POOL, loans).with_fee(Fee::Pips(U256::from(500u64))),
+        None,
+    );
+
+    assert_eq!(encoder.calls(), expected.calls());
+}
+
+#[test]
+fn test_uni_v2_flash_swap_repays_fee() {
+    use executooor::callbacks::UNISWAP_V2_CALL;
+    use executooor::protocols::flashloans::Repayment;
+
+    alloy_sol_types::sol! {
+        function flash(address receiver, uint256 amount0, uint256 amount1, bytes data) external;
+    }
+
+    let amount = U256::from(1_000_000u64);
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.uni_v2_flash_swap(AAVE_V2_POOL, [DAI, WETH], [amount, U256::ZERO], None);
+
+    // The flash swap approving `repaid` DAI back to the pair.
+    let repaying = |repaid: u64| {
+        let loans = vec![
+            AssetRequest {
+                asset: DAI,
+                amount: U256::from(repaid),
+            },
+            AssetRequest {
+                asset: WETH,
+                amount: U256::ZERO,
+            },
+        ];
+        let mut expected = ExecutorEncoder::new(EXECUTOR);
+        expected.callback_call(
+            AAVE_V2_POOL,
+            flashCall {
+                receiver: EXECUTOR,
+                amount0: amount,
+                amount1: U256::ZERO,
+                data: Bytes::new(),
+            },
+            &UNISWAP_V2_CALL.context(AAVE_V2_POOL),
+            Bytes::new(),
+            &Repayment::approve(AAVE_V2_POOL, loans),
+            None,
+        );
+        expected.flush()
+    };
+
+    // Used to approve the bare loan, now adds 1_000_000 * 3 / 997 = 3009.03, rounded up.
+    assert_ne!(encoder.calls(), repaying(1_000_000));
+    assert_eq!(encoder.calls(), repaying(1_003_010));
+}
+
+#[test]
+fn test_try_callback_call_requires_trailing_empty_bytes() {
+    use executooor::callbacks::ON_FLASH_LOAN;
+    use executooor::protocols::flashloans::Repayment;
+
+    alloy_sol_types::sol! {
+        function loan(bytes data, uint256[] ids) external;
+    }
+
+    let call = || loanCall {
+        data: Bytes::new(),
+        ids: vec![U256::from(1u64)],
+    };
+    let ctx = ON_FLASH_LOAN.context(BALANCER_VAULT);
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    assert!(matches!(
+        encoder.try_callback_call(
+            BALANCER_VAULT,
+            call(),
+            &ctx,
+            Bytes::new(),
+            &Repayment::none(),
+            None
+        ),
+        Err(Error::InvalidCallbackCall(signature)) if signature == "loan(bytes,uint256[])"
+    ));
+
+    // The calls pushed by the callback are dropped along with their value.
+    let result = encoder.try_callback_call_with(
+        BALANCER_VAULT,
+        call(),
+        &ctx,
+        Bytes::new(),
+        &Repayment::none(),
+        |inner| {
+            inner.wrap_eth(WETH, U256::from(5u64));
+        },
+    );
+    assert!(matches!(result, Err(Error::InvalidCallbackCall(_))));
+    assert!(encoder.is_empty());
+    assert_eq!(encoder.value(), U256::ZERO);
+    let approve = ExecutorEncoder::build_erc20_approve(DAI, AAVE_V2_POOL, U256::from(1u64));
+    encoder.blue_flash_loan(BALANCER_VAULT, DAI, U256::from(1u64), Some(vec![approve]));
+    assert_eq!(encoder.value(), U256::ZERO);
+}
+
+#[test]
+fn test_empty_callback_nests_nothing() {
+    use executooor::callbacks::ON_FLASH_LOAN;
+    use executooor::protocols::flashloans::Repayment;
+
+    alloy_sol_types::sol! {
+        function borrow(uint256 amount, bytes data) external;
+    }
+
+    let call = || borrowCall {
+        amount: U256::from(1u64),
+        data: Bytes::new(),
+    };
+    let ctx = ON_FLASH_LOAN.context(BALANCER_VAULT);
+    let pending = || {
+        let mut encoder = ExecutorEncoder::new(EXECUTOR);
+        encoder.wrap_eth(WETH, U256::from(5u64));
+        let calls = encoder.flush();
+        (encoder, calls)
+    };
+
+    let (mut pushed, calls) = pending();
+    pushed.push_callback_call(
+        BALANCER_VAULT,
+        U256::ZERO,
+        call(),
+        &ctx,
+        vec![],
+        Bytes::new(),
+    );
+    let (mut flash, _) = pending();
+    flash.callback_call(
+        BALANCER_VAULT,
+        call(),
+        &ctx,
+        Bytes::new(),
+        &Repayment::none(),
+        Some(vec![]),
+    );
+    assert_eq!(pushed.value(), flash.value());
+    assert!(pushed.metadata()[0].callback.is_empty());
+    assert!(flash.metadata()[0].callback.is_empty());
+
+    // The pending calls are attached to the next non-empty callback.
+    pushed.push_callback_call(
+        BALANCER_VAULT,
+        U256::ZERO,
+        call(),
+        &ctx,
+        calls,
+        Bytes::new(),
+    );
+    assert_eq!(pushed.metadata()[1].callback.len(), 1);
+    pushed.remove_call(1).unwrap();
+    assert_eq!(pushed.value(), U256::ZERO);
+}
+
+#[test]
+fn test_repayment_calls() {
+    use std::sync::Arc;
+
+    use executooor::protocols::flashloans::{Fee, Repayment};
+
+    let amount = U256::from(10_000u64);
+    let loans = vec![AssetRequest { asset: DAI, amount }];
+    assert!(Repayment::none().calls().is_empty());
+
+    assert_eq!(Fee::None.of(amount), U256::ZERO);
+    assert_eq!(Fee::Percent(U256::from(9u64)).of(amount), U256::from(9u64));
+    assert_eq!(
+        Fee::Pips(U256::from(3000u64)).of(U256::from(1u64)),
+        U256::from(1u64)
+    );
+    let bps = U256::from(100u64);
+    let fee = Fee::Custom(Arc::new(move |amount| amount * bps / U256::from(10_000u64)));
+    assert_eq!(fee.of(amount), U256::from(100u64));
+    assert_eq!(format!("{fee:?}"), "Custom(..)");
+
+    let repayment = Repayment::transfer(BALANCER_VAULT, loans.clone()).with_fee(fee);
+    assert_eq!(
+        repayment.calls(),
+        vec![ExecutorEncoder::build_erc20_transfer(
+            DAI,
+            BALANCER_VAULT,
+            U256::from(10_100u64)
+        )]
+    );
+    let repayment = Repayment::approve(AAVE_V2_POOL, loans);
+    assert_eq!(
+        repayment.calls(),
+        vec![ExecutorEncoder::build_erc20_approve(
+            DAI,
+            AAVE_V2_POOL,
+            amount
+        )]
+    );
+}
+
+// ============================================================
+// 35. Multicall3 placeholders
+// ============================================================
+#[test]
+fn test_multicall3_placeholders() {
+    use executooor::decoder::decode_call;
+    use executooor::protocols::multicall3::MULTICALL3;
+
+    let encoder = ExecutorEncoder::new(EXECUTOR);
+    let sources = [
+        (
+            encoder.multicall3_block_timestamp(MULTICALL3, 4),
+            "0f28c97d",
+        ),
+        (encoder.multicall3_basefee(MULTICALL3, 4), "3e64a696"),
+        (encoder.multicall3_block_number(MULTICALL3, 4), "42cbb15c"),
+        (encoder.multicall3_coinbase(MULTICALL3, 4), "a8b0574e"),
+    ];
+    for (placeholder, selector) in sources {
+        assert_eq!(placeholder.to, MULTICALL3);
+        assert_eq!(placeholder.data, h(selector));
+        assert_eq!(placeholder.offset, 4);
+        assert_eq!(placeholder.length, 32);
+        assert_eq!(placeholder.resOffset, 0);
+    }
+
+    let balance = encoder.multicall3_eth_balance(MULTICALL3, EXECUTOR, 36);
+    assert_eq!(
+        balance.data,
+        h("4d2301cc0000000000000000000000001111111111111111111111111111111111111111")
+    );
+    assert_eq!(balance.offset, 36);
+
+    // Results are known to be 32 bytes: reading past them is rejected.
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    let mut past_end = balance.clone();
+    past_end.resOffset = 32;
+    let call_data = Bytes::from(vec![0u8; 68]);
+    assert!(matches!(
+        encoder.try_push_call(DAI, U256::ZERO, call_data.clone(), None, vec![past_end]),
+        Err(Error::PlaceholderResultOutOfBounds { .. })
+    ));
+    encoder.push_call(DAI, U256::ZERO, call_data, None, vec![balance.clone()]);
+    let call = decode_call(&encoder.calls()[0]).unwrap();
+    assert_eq!(call.placeholders, vec![balance]);
+}
+
+#[test]
+fn test_uni_v3_exact_input_now() {
+    use executooor::decoder::decode_call;
+    use executooor::protocols::multicall3::MULTICALL3;
+
+    let path = h(
+        "6B175474E89094C44Da98b954EedeAC495271d0F0001f4C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+    );
+    let router = address!("E592427A0AEce92De3Edee1F18E0157C05861564");
+    let amount = U256::from(1000u64);
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.uni_v3_exact_input_now(router, path.clone(), amount, U256::ZERO, MULTICALL3, None);
+    let mut expected = ExecutorEncoder::new(EXECUTOR);
+    expected.uni_v3_exact_input(router, path, amount, U256::ZERO, U256::ZERO, None);
+
+    let call = decode_call(&encoder.calls()[0]).unwrap();
+    let expected_call = decode_call(&expected.calls()[0]).unwrap();
+    assert_eq!(call.call_data, expected_call.call_data);
+    let mut placeholder = encoder.multicall3_block_timestamp(MULTICALL3, 0);
+    placeholder.offset = call.placeholders[0].offset;
+    assert_eq!(call.placeholders, vec![placeholder]);
+
+    // The timestamp lands in the deadline.
+    let mut data = call.call_data.to_vec();
+    let offset = call.placeholders[0].offset as usize;
+    data[offset..offset + 32].copy_from_slice(&U256::from(42u64).to_be_bytes::<32>());
+    let params = <layout_sol::exactInputCall as alloy_sol_types::SolCall>::abi_decode(&data)
+        .unwrap()
+        .params;
+    assert_eq!(params.deadline, U256::from(42u64));
+    assert_eq!(params.amountIn, amount);
+}
+
+// ============================================================
+// 36. full-balance native ETH operations
+// ============================================================
+#[test]
+fn test_wrap_all_eth() {
+    use executooor::decoder::decode_call;
+    use executooor::protocols::multicall3::MULTICALL3;
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_all_eth(WETH, MULTICALL3);
+    assert_eq!(encoder.value(), U256::ZERO);
+
+    // The executor calls itself, with the deposit value filled by the ETH balance.
+    let call = decode_call(&encoder.calls()[0]).unwrap();
+    assert_eq!(call.target, EXECUTOR);
+    assert_eq!(call.value, U256::ZERO);
+    assert_eq!(call.context, CallbackContext::default());
+    let mut placeholder = encoder.multicall3_eth_balance(MULTICALL3, EXECUTOR, 0);
+    placeholder.offset = call.placeholders[0].offset;
+    assert_eq!(call.placeholders, vec![placeholder]);
+
+    let deposit = decode_call(&call.call_data).unwrap();
+    assert_eq!(deposit.target, WETH);
+    assert_eq!(deposit.value, U256::ZERO);
+    assert_eq!(deposit.context, CallbackContext::default());
+    assert_eq!(deposit.call_data, h("d0e30db0"));
+    assert!(deposit.placeholders.is_empty());
+
+    // The balance lands in the deposit value.
+    let mut data = call.call_data.to_vec();
+    let offset = call.placeholders[0].offset as usize;
+    data[offset..offset + 32].copy_from_slice(&U256::from(42u64).to_be_bytes::<32>());
+    assert_eq!(decode_call(&data).unwrap().value, U256::from(42u64));
+}
+
+#[test]
+fn test_unwrap_all_eth() {
+    use executooor::decoder::decode_call;
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.unwrap_all_eth(WETH);
+
+    let call = decode_call(&encoder.calls()[0]).unwrap();
+    assert_eq!(call.target, WETH);
+    // withdraw(0), with `wad` filled by the WETH balance.
+    assert_eq!(
+        call.call_data,
+        h("2e1a7d4d0000000000000000000000000000000000000000000000000000000000000000")
+    );
+    assert_eq!(
+        call.placeholders,
+        vec![encoder.erc20_balance_of(WETH, EXECUTOR, 4)]
+    );
+}
+
+#[test]
+fn test_sweep_eth() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.sweep_eth(DAI);
+    let mut expected = ExecutorEncoder::new(EXECUTOR);
+    expected.transfer(DAI, U256::MAX);
+    assert_eq!(encoder.calls(), expected.calls());
+    assert_eq!(encoder.value(), U256::ZERO);
+
+    assert!(matches!(
+        encoder.try_sweep_eth(Address::ZERO),
+        Err(Error::ZeroRecipient)
+    ));
+    assert_eq!(encoder.len(), 1);
+}
@@ -0,0 +1,149 @@
+use alloy_primitives::{address, Address, Bytes, U256};
+use executooor::decoder::decode_exec;
+use executooor::encoder::{EncodedExec, ExecutorEncoder};
+use executooor::explain::{explain_exec, Explainer};
+use executooor::types::AssetRequest;
+
+const DAI: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+const BALANCER_VAULT: Address = address!("BA12222222228d8Ba445958a75a0704d566BF2C8");
+const AAVE_V2_POOL: Address = address!("7d2768dE32b0b80b7a3454c06BdAc94A69DDc7A9");
+const EXECUTOR: Address = address!("1111111111111111111111111111111111111111");
+
+fn explainer() -> Explainer {
+    Explainer::new()
+        .label(DAI, "DAI")
+        .label(WETH, "WETH")
+        .label(BALANCER_VAULT, "balancer")
+        .label(AAVE_V2_POOL, "aave")
+        .label(EXECUTOR, "executor")
+}
+
+// ============================================================
+// 1. flat batch with value
+// ============================================================
+#[test]
+fn test_explain_flat() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .erc20_approve(DAI, AAVE_V2_POOL, U256::from(500u64))
+        .wrap_eth(WETH, U256::from(1000u64))
+        .tip(U256::from(1u64));
+    let EncodedExec { data, .. } = encoder.encode_exec(U256::ZERO);
+
+    let expected = "\
+DAI.approve(aave, 500)
+WETH.deposit() {value: 1000}
+executor.transfer(0x0000000000000000000000000000000000000000, 1)
+";
+    assert_eq!(explainer().explain_exec(&data).unwrap(), expected);
+}
+
+// ============================================================
+// 2. placeholders annotate their source staticcall
+// ============================================================
+#[test]
+fn test_explain_placeholders() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .erc20_skim(DAI, BALANCER_VAULT)
+        .uni_v3_exact_input_all(
+            BALANCER_VAULT,
+            Bytes::from(DAI.to_vec()),
+            U256::ZERO,
+            U256::MAX,
+            None,
+        );
+    let EncodedExec { data, .. } = encoder.encode_exec(U256::ZERO);
+
+    let explained = explainer().explain_exec(&data).unwrap();
+    let lines: Vec<&str> = explained.lines().collect();
+    assert_eq!(
+        lines[0],
+        "DAI.transfer(balancer, <DAI.balanceOf(executor)>)"
+    );
+    assert!(lines[1].starts_with("balancer.exactInput(("));
+    assert_eq!(lines[2], "  calldata[132..164] <- DAI.balanceOf(executor)");
+}
+
+// ============================================================
+// 3. callbacks are indented
+// ============================================================
+#[test]
+fn test_explain_callback() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.aave_supply(AAVE_V2_POOL, DAI, U256::from(100u64), None);
+    let inner = encoder.flush();
+    encoder.balancer_flash_loan(
+        BALANCER_VAULT,
+        &[AssetRequest {
+            asset: DAI,
+            amount: U256::from(100u64),
+        }],
+        Some(inner),
+    );
+    let EncodedExec { data, .. } = encoder.encode_exec(U256::ZERO);
+
+    let explained = explainer().explain_exec(&data).unwrap();
+    let lines: Vec<&str> = explained.lines().collect();
+    assert!(lines[0].starts_with("balancer.flashLoan(executor, [DAI], [100], <"));
+    assert_eq!(lines[1], "  callback from balancer (data index 3):");
+    assert_eq!(lines[2], "    aave.deposit(DAI, 100, executor, 0)");
+    assert_eq!(lines[3], "    DAI.transfer(balancer, 100)");
+    assert_eq!(lines.len(), 4);
+}
+
+// ============================================================
+// 4. unknown selectors fall back to raw hex
+// ============================================================
+#[test]
+fn test_explain_unknown_selector() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.push_call(
+        DAI,
+        U256::ZERO,
+        Bytes::from(vec![0xde, 0xad, 0xbe, 0xef, 0x01]),
+        None,
+        vec![],
+    );
+    let EncodedExec { data, .. } = encoder.encode_exec(U256::ZERO);
+
+    assert_eq!(
+        explain_exec(&data).unwrap(),
+        format!("{DAI}.0xdeadbeef(0x01)\n")
+    );
+}
+
+// ============================================================
+// 5. labels and tags from encoder metadata
+// ============================================================
+#[test]
+fn test_explain_with_metadata() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .balancer_flash_loan_with(
+            BALANCER_VAULT,
+            &[AssetRequest {
+                asset: DAI,
+                amount: U256::from(100u64),
+            }],
+            |inner| {
+                inner
+                    .aave_supply(AAVE_V2_POOL, DAI, U256::from(100u64), None)
+                    .label("supply")
+                    .tag("asset", "DAI");
+            },
+        )
+        .label("flash loan");
+    let (exec, metadata) = encoder.encode_exec_with_metadata(U256::ZERO);
+
+    let calls = decode_exec(&exec.data).unwrap();
+    let explained = explainer().explain_with_metadata(&calls, &metadata);
+    let lines: Vec<&str> = explained.lines().collect();
+    assert!(lines[0].ends_with(") # flash loan"));
+    assert_eq!(
+        lines[2],
+        "    aave.deposit(DAI, 100, executor, 0) # supply (asset=DAI)"
+    );
+    assert_eq!(lines[3], "    DAI.transfer(balancer, 100)");
+}
@@ -0,0 +1,189 @@
+use alloy_primitives::{address, Address, U256};
+use executooor::encoder::ExecutorEncoder;
+use executooor::gas::{estimate_gas, CalldataGas, TX_BASE_GAS};
+use executooor::l1_fee::{
+    arbitrum_l1_fee, arbitrum_l1_fee_for_size, fastlz_compressed_len, fjord_estimated_size,
+    op_ecotone_l1_fee, op_fjord_l1_fee, OpFeeParams,
+};
+use executooor::types::AssetRequest;
+
+const DAI: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+const BALANCER_VAULT: Address = address!("BA12222222228d8Ba445958a75a0704d566BF2C8");
+const EXECUTOR: Address = address!("1111111111111111111111111111111111111111");
+
+// ============================================================
+// 1. calldata pricing
+// ============================================================
+#[test]
+fn test_calldata_gas_pricing() {
+    let gas = CalldataGas::of(&[0, 0, 1, 0xff, 0]);
+    assert_eq!(gas.zero_bytes, 3);
+    assert_eq!(gas.non_zero_bytes, 2);
+    assert_eq!(gas.tokens(), 3 + 4 * 2);
+    assert_eq!(gas.cost(), 3 * 4 + 2 * 16);
+    assert_eq!(gas.floor_cost(), 10 * 11);
+}
+
+// ============================================================
+// 2. intrinsic gas and floor of a flat batch
+// ============================================================
+#[test]
+fn test_estimate_flat() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(1000u64)).erc20_transfer(
+        DAI,
+        BALANCER_VAULT,
+        U256::from(500u64),
+    );
+    let exec = encoder.encode_exec(U256::ZERO);
+
+    let estimate = estimate_gas(&exec).unwrap();
+    let calldata = CalldataGas::of(&exec.data);
+    assert_eq!(estimate.calldata, calldata);
+    assert_eq!(estimate.intrinsic_gas, TX_BASE_GAS + calldata.cost());
+    assert_eq!(estimate.floor_gas, TX_BASE_GAS + calldata.floor_cost());
+
+    // Cheap execution is charged the floor, expensive execution is charged on top.
+    assert_eq!(estimate.gas_used(0), estimate.floor_gas);
+    assert_eq!(
+        estimate.gas_used(1_000_000),
+        estimate.intrinsic_gas + 1_000_000
+    );
+
+    assert_eq!(estimate.calls.len(), 2);
+    assert_eq!(estimate.calls[0].target, WETH);
+    assert_eq!(estimate.calls[1].target, DAI);
+    assert!(estimate.calls.iter().all(|call| call.callback.is_empty()));
+}
+
+// ============================================================
+// 3. breakdown per call and per nested callback
+// ============================================================
+#[test]
+fn test_estimate_nested_breakdown() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .balancer_flash_loan_with(
+            BALANCER_VAULT,
+            &[AssetRequest {
+                asset: DAI,
+                amount: U256::from(1000u64),
+            }],
+            |inner| {
+                inner.erc20_approve(DAI, WETH, U256::from(1000u64));
+            },
+        )
+        .erc20_skim(DAI, EXECUTOR);
+    let calls = encoder.calls().to_vec();
+    let exec = encoder.encode_exec(U256::ZERO);
+
+    let estimate = estimate_gas(&exec).unwrap();
+    assert_eq!(estimate.calls.len(), 2);
+    for (call, encoded) in estimate.calls.iter().zip(&calls) {
+        assert_eq!(call.calldata, CalldataGas::of(encoded));
+    }
+
+    let flash_loan = &estimate.calls[0];
+    assert_eq!(flash_loan.target, BALANCER_VAULT);
+    // approve, then the repayment transfer.
+    assert_eq!(flash_loan.callback.len(), 2);
+    let nested: u64 = flash_loan
+        .callback
+        .iter()
+        .map(|call| call.calldata.cost())
+        .sum();
+    assert!(nested < flash_loan.calldata.cost());
+}
+
+// ============================================================
+// 4. FastLZ compressed size
+// ============================================================
+#[test]
+fn test_fastlz_compressed_len() {
+    assert_eq!(fastlz_compressed_len(&[]), 0);
+    // Too short to search matches: a single literal run with its header.
+    assert_eq!(fastlz_compressed_len(&[1, 2, 3, 4, 5]), 6);
+    let distinct: Vec<u8> = (0..32).collect();
+    assert_eq!(fastlz_compressed_len(&distinct), 33);
+    // 2 literals, a 3-byte match over the run, 5 trailing literals.
+    assert_eq!(fastlz_compressed_len(&[0; 64]), 3 + 3 + 6);
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    for _ in 0..10 {
+        encoder.erc20_transfer(DAI, BALANCER_VAULT, U256::from(500u64));
+    }
+    let exec = encoder.encode_exec(U256::ZERO);
+    assert!(fastlz_compressed_len(&exec.data) < exec.data.len() / 4);
+}
+
+// ============================================================
+// 5. OP Stack and Arbitrum L1 data fees
+// ============================================================
+fn op_params() -> OpFeeParams {
+    OpFeeParams {
+        l1_base_fee: U256::from(10_000_000_000u64),
+        blob_base_fee: U256::from(1u64),
+        base_fee_scalar: 2_269,
+        blob_base_fee_scalar: 1_055_762,
+    }
+}
+
+#[test]
+fn test_op_ecotone_l1_fee() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(1000u64));
+    let exec = encoder.encode_exec(U256::ZERO);
+
+    let l1_gas_used = U256::from(CalldataGas::of(&exec.data).cost() + 68 * 16);
+    let scaled_fee = U256::from(16 * 10_000_000_000u64 * 2_269 + 1_055_762);
+    assert_eq!(
+        op_ecotone_l1_fee(&exec, &op_params()),
+        l1_gas_used * scaled_fee / U256::from(16_000_000u64)
+    );
+}
+
+#[test]
+fn test_op_fjord_l1_fee() {
+    let scaled_fee = U256::from(16 * 10_000_000_000u64 * 2_269 + 1_055_762);
+
+    // Small transactions are charged the minimum size.
+    assert_eq!(fjord_estimated_size(0), 100_000_000);
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(1000u64));
+    let exec = encoder.encode_exec(U256::ZERO);
+    assert_eq!(
+        op_fjord_l1_fee(&exec, &op_params()),
+        U256::from(100_000_000u64) * scaled_fee / U256::from(1_000_000_000_000u64)
+    );
+
+    assert_eq!(fjord_estimated_size(1_000), 836_500_000 - 42_585_600);
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    for i in 0..20u64 {
+        encoder.erc20_transfer(DAI, Address::with_last_byte(i as u8), U256::from(i));
+    }
+    let exec = encoder.encode_exec(U256::ZERO);
+    let fastlz_size = fastlz_compressed_len(&exec.data) as u64 + 68;
+    assert_eq!(
+        op_fjord_l1_fee(&exec, &op_params()),
+        U256::from(fjord_estimated_size(fastlz_size)) * scaled_fee
+            / U256::from(1_000_000_000_000u64)
+    );
+}
+
+#[test]
+fn test_arbitrum_l1_fee() {
+    let price = U256::from(30_000_000_000u64);
+    assert_eq!(
+        arbitrum_l1_fee_for_size(100, price),
+        U256::from(1_600u64) * price
+    );
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.wrap_eth(WETH, U256::from(1000u64));
+    let exec = encoder.encode_exec(U256::ZERO);
+    assert_eq!(
+        arbitrum_l1_fee(&exec, price),
+        arbitrum_l1_fee_for_size(fastlz_compressed_len(&exec.data) as u64, price)
+    );
+}
@@ -0,0 +1,178 @@
+#![cfg(feature = "macros")]
+
+use alloy_primitives::{address, Address, U256};
+use executooor::encoder::ExecutorEncoder;
+
+const DAI: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+const VAULT: Address = address!("83F20F44975D03b1b09e64809B757c47f942BEeA");
+const MORPHO_BLUE: Address = address!("BBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb");
+const EXECUTOR: Address = address!("1111111111111111111111111111111111111111");
+
+executooor::sol_encoder! {
+    /// ERC4626 vaults, generated.
+    #[encoder(prefix = "vault")]
+    interface VaultEncoder {
+        /// Deposits `assets` for `receiver`.
+        #[encoder(balance_of(assets))]
+        function deposit(uint256 assets, address receiver) external returns (uint256);
+        function maxWithdraw(address owner) external view returns (uint256);
+    }
+}
+
+executooor::sol_encoder! {
+    #[encoder(prefix = "lender")]
+    interface LenderEncoder {
+        #[encoder(callback(data = data, function = "onMorphoFlashLoan"))]
+        function flashLoan(address token, uint256 assets, bytes data) external;
+
+        #[encoder(callback(data = data, function = "onLoan(address,uint256,bytes)"))]
+        function loan(address token, uint256 assets, bytes data) external;
+
+        #[encoder(balance_of(assets, token = loanToken))]
+        function supplyCollateral(MarketParams marketParams, address loanToken, uint256 assets, address onBehalf) external;
+
+        struct MarketParams {
+            address loanToken;
+            address collateralToken;
+            address oracle;
+            address irm;
+            uint256 lltv;
+        }
+    }
+}
+
+executooor::sol_encoder! {
+    #[encoder(prefix = "pool")]
+    interface PoolEncoder {
+        #[encoder(balance_of(amount))]
+        function join(bytes data, uint256[2] limits, Side side, Amount min, uint256 amount) external;
+
+        enum Side {
+            Buy,
+            Sell
+        }
+
+        type Amount is uint128;
+    }
+}
+
+executooor::sol_encoder! {
+    interface Weth {
+        function deposit() external payable;
+    }
+}
+
+// ============================================================
+// 1. generated methods match the hand-written protocols
+// ============================================================
+#[test]
+fn test_generated_calls_match_protocols() {
+    let mut generated = ExecutorEncoder::new(EXECUTOR);
+    generated
+        .vault_deposit(VAULT, U256::from(100u64), EXECUTOR)
+        .vault_deposit_all(VAULT, DAI, EXECUTOR);
+    Weth::deposit(&mut generated, WETH, U256::from(5u64));
+
+    let mut expected = ExecutorEncoder::new(EXECUTOR);
+    expected
+        .erc4626_deposit(VAULT, U256::from(100u64), EXECUTOR)
+        .erc4626_deposit_all(VAULT, DAI, EXECUTOR)
+        .wrap_eth(WETH, U256::from(5u64));
+
+    assert_eq!(generated.calls(), expected.calls());
+    assert_eq!(generated.value(), U256::from(5u64));
+    assert_eq!(
+        generated.vault_max_withdraw(VAULT, EXECUTOR, 36),
+        expected.erc4626_max_withdraw(VAULT, EXECUTOR, 36)
+    );
+}
+
+// ============================================================
+// 2. callback annotation
+// ============================================================
+#[test]
+fn test_generated_callback() {
+    let amount = U256::from(1000u64);
+    let mut generated = ExecutorEncoder::new(EXECUTOR);
+    generated.lender_flash_loan_with(MORPHO_BLUE, WETH, amount, |inner| {
+        inner
+            .wrap_eth(WETH, U256::from(1u64))
+            .erc20_approve(WETH, MORPHO_BLUE, amount);
+    });
+
+    let mut expected = ExecutorEncoder::new(EXECUTOR);
+    let inner = expected.build_callback(|inner| {
+        inner.wrap_eth(WETH, U256::from(1u64));
+    });
+    expected.blue_flash_loan(MORPHO_BLUE, WETH, amount, Some(inner));
+
+    assert_eq!(generated.calls(), expected.calls());
+    assert_eq!(generated.value(), U256::from(1u64));
+
+    let call = executooor::decoder::decode_call(&generated.calls()[0]).unwrap();
+    assert_eq!(call.context.sender, MORPHO_BLUE);
+    assert_eq!(call.context.data_index, 1);
+    assert_eq!(call.callback.unwrap().calls.len(), 2);
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.lender_loan(MORPHO_BLUE, WETH, amount, None);
+    let call = executooor::decoder::decode_call(&encoder.calls()[0]).unwrap();
+    assert_eq!(call.context.data_index, 2);
+}
+
+// ============================================================
+// 3. balance placeholders after dynamic arguments and structs
+// ============================================================
+#[test]
+fn test_generated_balance_of_struct_argument() {
+    let market = lender_encoder_sol::MarketParams {
+        loanToken: DAI,
+        collateralToken: WETH,
+        oracle: Address::ZERO,
+        irm: Address::ZERO,
+        lltv: U256::from(860_000_000_000_000_000u64),
+    };
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.lender_supply_collateral_all(MORPHO_BLUE, market, DAI, EXECUTOR);
+
+    let call = executooor::decoder::decode_call(&encoder.calls()[0]).unwrap();
+    let placeholder = &call.placeholders[0];
+    // The 5-word struct and `loanToken` come first.
+    assert_eq!(placeholder.offset, 4 + 32 * 6);
+    assert_eq!(placeholder.to, DAI);
+    assert_eq!(
+        placeholder.data,
+        encoder.erc20_balance_of(DAI, EXECUTOR, 0).data
+    );
+    assert_eq!(call.call_data.len(), 4 + 32 * 8);
+    assert_eq!(call.value, U256::ZERO);
+}
+
+#[test]
+fn test_generated_balance_of_offset_matches_abi() {
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.pool_join_all(
+        VAULT,
+        DAI,
+        alloy_primitives::Bytes::from(vec![0u8; 40]),
+        [U256::ZERO; 2],
+        pool_encoder_sol::Side::Sell,
+        1u128,
+    );
+
+    let call = executooor::decoder::decode_call(&encoder.calls()[0]).unwrap();
+    let expected = pool_encoder_sol::joinCall {
+        data: alloy_primitives::Bytes::from(vec![0u8; 40]),
+        limits: [U256::ZERO; 2],
+        side: pool_encoder_sol::Side::Sell,
+        min: 1u128,
+        amount: U256::ZERO,
+    };
+    // The `data` offset word, the 2 limits, `side` and `min` come first.
+    assert_eq!(call.placeholders[0].offset, 4 + 32 * 5);
+    assert_eq!(
+        executooor::sol_arg_offset(&expected, "amount").unwrap(),
+        call.placeholders[0].offset
+    );
+}
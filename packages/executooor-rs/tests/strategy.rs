@@ -0,0 +1,182 @@
+#![cfg(feature = "strategy")]
+
+use alloy_primitives::{address, Address, U256};
+use executooor::encoder::ExecutorEncoder;
+use executooor::strategy::{Strategy, StrategyAction};
+use executooor::types::MarketParams;
+use executooor::Error;
+
+const DAI: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+const MORPHO_BLUE: Address = address!("BBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb");
+const SDAI: Address = address!("83F20F44975D03b1b09e64809B757c47f942BEeA");
+const EXECUTOR: Address = address!("1111111111111111111111111111111111111111");
+
+// ============================================================
+// 1. TOML strategies compile like the equivalent builder calls
+// ============================================================
+#[test]
+fn test_compile_toml() {
+    let strategy = Strategy::from_toml(
+        r#"
+        executor = "0x1111111111111111111111111111111111111111"
+        extraValue = "5"
+
+        [[actions]]
+        action = "wrap_eth"
+        weth = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
+        amount = "100"
+
+        [[actions]]
+        action = "blue_flash_loan"
+        morphoBlue = "0xBBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb"
+        asset = "0x6B175474E89094C44Da98b954EedeAC495271d0F"
+        amount = "1000"
+
+        [[actions.callback]]
+        action = "erc20_approve"
+        asset = "0x6B175474E89094C44Da98b954EedeAC495271d0F"
+        spender = "0xBBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb"
+        allowance = "1000"
+        "#,
+    )
+    .unwrap();
+
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder
+        .wrap_eth(WETH, U256::from(100u64))
+        .blue_flash_loan_with(MORPHO_BLUE, DAI, U256::from(1000u64), |inner| {
+            inner.erc20_approve(DAI, MORPHO_BLUE, U256::from(1000u64));
+        });
+
+    assert_eq!(
+        strategy.compile().unwrap(),
+        encoder.encode_exec(U256::from(5u64))
+    );
+}
+
+// ============================================================
+// 2. JSON strategies
+// ============================================================
+#[test]
+fn test_compile_json() {
+    let strategy = Strategy::from_json(
+        r#"{
+            "executor": "0x1111111111111111111111111111111111111111",
+            "actions": [
+                {
+                    "action": "balancer_flash_loan",
+                    "vault": "0xBA12222222228d8Ba445958a75a0704d566BF2C8",
+                    "requests": [{ "asset": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", "amount": "7" }],
+                    "callback": [
+                        { "action": "unwrap_eth", "weth": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", "amount": "7" },
+                        { "action": "wrap_eth", "weth": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", "amount": "7" }
+                    ]
+                }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        &strategy.actions[0],
+        StrategyAction::BalancerFlashLoan(loan) if loan.callback.len() == 2
+    ));
+    assert_eq!(strategy.compile().unwrap().value, U256::from(7u64));
+}
+
+#[test]
+fn test_compile_protocol_actions() {
+    let strategy = Strategy::from_toml(
+        r#"
+        executor = "0x1111111111111111111111111111111111111111"
+
+        [[actions]]
+        action = "morpho_blue_liquidate"
+        morphoBlue = "0xBBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb"
+        borrower = "0x1111111111111111111111111111111111111111"
+        seizedAssets = "10"
+        repaidShares = "0"
+        market = { loanToken = "0x6B175474E89094C44Da98b954EedeAC495271d0F", collateralToken = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", oracle = "0x0000000000000000000000000000000000000000", irm = "0x0000000000000000000000000000000000000000", lltv = "0" }
+
+        [[actions.callback]]
+        action = "erc4626_redeem_all"
+        vault = "0x83F20F44975D03b1b09e64809B757c47f942BEeA"
+        asset = "0x6B175474E89094C44Da98b954EedeAC495271d0F"
+        receiver = "0x1111111111111111111111111111111111111111"
+        owner = "0x1111111111111111111111111111111111111111"
+        "#,
+    )
+    .unwrap();
+
+    let market = MarketParams {
+        loan_token: DAI,
+        collateral_token: WETH,
+        oracle: Address::ZERO,
+        irm: Address::ZERO,
+        lltv: U256::ZERO,
+    };
+    let mut encoder = ExecutorEncoder::new(EXECUTOR);
+    encoder.morpho_blue_liquidate_with(
+        MORPHO_BLUE,
+        &market,
+        EXECUTOR,
+        U256::from(10u64),
+        U256::ZERO,
+        |inner| {
+            inner.erc4626_redeem_all(SDAI, EXECUTOR, EXECUTOR);
+        },
+    );
+
+    assert_eq!(strategy.compile().unwrap(), encoder.encode_exec(U256::ZERO));
+}
+
+// ============================================================
+// 3. errors
+// ============================================================
+#[test]
+fn test_invalid_strategy() {
+    let unknown_action = Strategy::from_toml(
+        r#"
+        executor = "0x1111111111111111111111111111111111111111"
+        [[actions]]
+        action = "rug_pull"
+        "#,
+    );
+    assert!(matches!(unknown_action, Err(Error::InvalidStrategy(_))));
+
+    let unknown_field = Strategy::from_json(
+        r#"{
+            "executor": "0x1111111111111111111111111111111111111111",
+            "actions": [{ "action": "tip", "amount": "1", "to": "0x1111111111111111111111111111111111111111" }]
+        }"#,
+    );
+    assert!(matches!(unknown_field, Err(Error::InvalidStrategy(_))));
+
+    // Every key is camelCase, including those of protocol actions.
+    let snake_case = Strategy::from_json(
+        r#"{
+            "executor": "0x1111111111111111111111111111111111111111",
+            "actions": [{
+                "action": "blue_flash_loan",
+                "morpho_blue": "0xBBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb",
+                "asset": "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+                "amount": "1"
+            }]
+        }"#,
+    );
+    assert!(matches!(snake_case, Err(Error::InvalidStrategy(_))));
+}
+
+#[test]
+fn test_compile_propagates_builder_errors() {
+    let strategy = Strategy {
+        executor: EXECUTOR,
+        extra_value: U256::ZERO,
+        actions: vec![StrategyAction::Transfer {
+            recipient: Address::ZERO,
+            amount: U256::from(1u64),
+        }],
+    };
+    assert!(matches!(strategy.compile(), Err(Error::ZeroRecipient)));
+}